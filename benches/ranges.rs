@@ -0,0 +1,5 @@
+use range_perf::harness::{self, selection::Options};
+
+fn main() {
+    harness::run(&Options::from_args(std::env::args().skip(1)));
+}
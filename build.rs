@@ -0,0 +1,49 @@
+//! Records how the crate is built, so the benchmark results can tell. Only the harness needs it.
+
+use std::env;
+use std::process::Command;
+
+/// Passes `value` to the crate as the compile-time environment variable `name`.
+fn set(name: &str, value: &str) {
+    // The values can't span several lines.
+    println!("cargo:rustc-env={}={}", name, value.replace('\n', " "));
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTFLAGS");
+    println!("cargo:rerun-if-env-changed=CARGO_ENCODED_RUSTFLAGS");
+    if env::var_os("CARGO_FEATURE_HARNESS").is_none() {
+        return;
+    }
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("-Vv")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    let line = |prefix: &str| {
+        version
+            .lines()
+            .find(|line| line.starts_with(prefix))
+            .unwrap_or("")
+            .to_owned()
+    };
+    set("RANGE_PERF_RUSTC", &line("rustc "));
+    set(
+        "RANGE_PERF_LLVM",
+        line("LLVM version: ").trim_start_matches("LLVM version: "),
+    );
+    set("RANGE_PERF_TARGET", &var("TARGET"));
+    set(
+        "RANGE_PERF_TARGET_FEATURES",
+        &var("CARGO_CFG_TARGET_FEATURE"),
+    );
+    set("RANGE_PERF_PROFILE", &var("PROFILE"));
+    set("RANGE_PERF_OPT_LEVEL", &var("OPT_LEVEL"));
+    set(
+        "RANGE_PERF_RUSTFLAGS",
+        &var("CARGO_ENCODED_RUSTFLAGS").replace('\x1f', " "),
+    );
+}
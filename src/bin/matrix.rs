@@ -0,0 +1,86 @@
+//! Runs the range benchmarks under a matrix of build profiles and toolchains.
+//!
+//! ```text
+//! range-perf-matrix [--toolchains current|all|<toolchain>,...] [--opt-level 2,3] [--lto off]
+//!                   [--codegen-units 16] [--target-cpu default,native]
+//!                   [-- <arguments of range-perf>]
+//! ```
+
+use range_perf::harness::export;
+use range_perf::harness::matrix::{self, Matrix};
+use std::env;
+use std::ffi::OsString;
+use std::process;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: range-perf-matrix [--toolchains current|all|<toolchain>,...] [--opt-level 2,3] \
+         [--lto off] [--codegen-units 16] [--target-cpu default,native] \
+         [-- <arguments of range-perf>]"
+    );
+    process::exit(2);
+}
+
+/// Splits a comma-separated list of values.
+fn list(values: &str) -> Vec<String> {
+    values
+        .split(',')
+        .map(|value| value.trim().to_owned())
+        .collect()
+}
+
+fn main() {
+    let mut matrix = Matrix::default();
+    let mut args = env::args_os().skip(1);
+    let mut passed: Vec<OsString> = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            passed.extend(args);
+            break;
+        }
+        let arg = arg.into_string().unwrap_or_else(|_| usage());
+        let values = match args.next().and_then(|values| values.into_string().ok()) {
+            Some(values) => list(&values),
+            None => usage(),
+        };
+        match arg.as_str() {
+            "--toolchains" if values == ["all"] => match matrix::installed_toolchains() {
+                Ok(toolchains) => matrix.toolchains = toolchains.into_iter().map(Some).collect(),
+                Err(error) => {
+                    eprintln!("Failed to list the toolchains: {}", error);
+                    process::exit(1);
+                }
+            },
+            "--toolchains" => {
+                matrix.toolchains = values
+                    .into_iter()
+                    .map(|name| if name == "current" { None } else { Some(name) })
+                    .collect()
+            }
+            "--opt-level" => matrix.opt_levels = values,
+            "--lto" => matrix.ltos = values,
+            "--codegen-units" => {
+                matrix.codegen_units = values
+                    .iter()
+                    .map(|value| value.parse().unwrap_or_else(|_| usage()))
+                    .collect()
+            }
+            "--target-cpu" => {
+                matrix.target_cpus = values
+                    .into_iter()
+                    .map(|cpu| if cpu == "default" { None } else { Some(cpu) })
+                    .collect()
+            }
+            _ => usage(),
+        }
+    }
+
+    let root = export::target_dir().join("matrix");
+    match matrix::run(&matrix, &passed, &root) {
+        Ok(path) => println!("The report is written to {}", path.display()),
+        Err(error) => {
+            eprintln!("Failed to run the matrix: {}", error);
+            process::exit(1);
+        }
+    }
+}
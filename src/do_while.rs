@@ -0,0 +1,74 @@
+use crate::inclusive::inclusive_size_hint;
+use crate::{InclusiveRange, Integer};
+use std::iter::FusedIterator;
+
+/// An inclusive range that works like a `do { ... } while start != end` loop: it yields `start`
+/// first and only then checks whether it has reached the end.
+///
+/// A flag is still needed to tell an exhausted (or initially empty) range apart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DoWhileRange<T> {
+    start: T,
+    end: T,
+    done: bool,
+}
+
+impl<T: Integer> DoWhileRange<T> {
+    /// Initializes a range that yields every value from `from` to `inclusive_to`, both ends
+    /// included.
+    pub fn new(from: T, inclusive_to: T) -> Self {
+        DoWhileRange {
+            start: from,
+            end: inclusive_to,
+            done: from > inclusive_to,
+        }
+    }
+}
+
+impl<T: Integer> Iterator for DoWhileRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let value = self.start;
+        if value == self.end {
+            self.done = true;
+        } else {
+            self.start = value.successor();
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            inclusive_size_hint(self.start, self.end)
+        }
+    }
+}
+
+impl<T: Integer> DoubleEndedIterator for DoWhileRange<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let value = self.end;
+        if value == self.start {
+            self.done = true;
+        } else {
+            self.end = value.predecessor();
+        }
+        Some(value)
+    }
+}
+
+impl<T: Integer> FusedIterator for DoWhileRange<T> {}
+
+impl<T: Integer> InclusiveRange<T> for DoWhileRange<T> {
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        DoWhileRange::new(from, inclusive_to)
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{InclusiveRange, Integer};
+use std::iter::{FusedIterator, Sum};
+use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
+
+/// A range-like iterator that decides at initialization whether to go with an *inclusive* or
+/// *non-inclusive* range under the hood depending on the upper bound value.
+///
+/// An inclusive range `from..=to` is only needed when `to` is the largest value of the type, since
+/// otherwise it is equivalent to `from..to + 1`, which the compiler handles much better.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DynamicInclusiveRange<T> {
+    /// The upper bound is `T::MAX`, so an inclusive range is the only option.
+    Inclusive(RangeInclusive<T>),
+    /// The upper bound is below `T::MAX` and has been turned into an exclusive one.
+    NonInclusive(Range<T>),
+}
+
+impl<T: Integer> DynamicInclusiveRange<T> {
+    /// Initializes a dynamic range that yields every value from `from` to `inclusive_to`, both
+    /// ends included.
+    pub fn new(from: T, inclusive_to: T) -> Self {
+        if inclusive_to == T::MAX {
+            DynamicInclusiveRange::Inclusive(from..=inclusive_to)
+        } else {
+            DynamicInclusiveRange::NonInclusive(from..inclusive_to.successor())
+        }
+    }
+
+    /// Initializes a dynamic range that yields the same values as `range`.
+    ///
+    /// Excluded bounds are normalized to included ones and unbounded ends are replaced with
+    /// `T::MIN` or `T::MAX`. A range that contains no values, like `(Excluded(T::MAX), Unbounded)`,
+    /// results in an empty iterator.
+    pub fn from_bounds<R: RangeBounds<T>>(range: R) -> Self {
+        let empty = DynamicInclusiveRange::NonInclusive(T::MIN..T::MIN);
+        let from = match range.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) if from == T::MAX => return empty,
+            Bound::Excluded(&from) => from.successor(),
+            Bound::Unbounded => T::MIN,
+        };
+        let inclusive_to = match range.end_bound() {
+            Bound::Included(&to) => to,
+            Bound::Excluded(&to) if to == T::MIN => return empty,
+            Bound::Excluded(&to) => to.predecessor(),
+            Bound::Unbounded => T::MAX,
+        };
+        DynamicInclusiveRange::new(from, inclusive_to)
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for DynamicInclusiveRange<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        // Goes through the bounds so an exhausted range stays exhausted.
+        DynamicInclusiveRange::from_bounds(range)
+    }
+}
+
+impl<T: Integer> InclusiveRange<T> for DynamicInclusiveRange<T>
+where
+    Range<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        DynamicInclusiveRange::new(from, inclusive_to)
+    }
+}
+
+impl<T> From<Range<T>> for DynamicInclusiveRange<T> {
+    fn from(range: Range<T>) -> Self {
+        DynamicInclusiveRange::NonInclusive(range)
+    }
+}
+
+impl<T> Iterator for DynamicInclusiveRange<T>
+where
+    Range<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.next(),
+            DynamicInclusiveRange::NonInclusive(r) => r.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.size_hint(),
+            DynamicInclusiveRange::NonInclusive(r) => r.size_hint(),
+        }
+    }
+
+    // The methods below match on the variant once and then let the underlying range do the
+    // internal iteration. `try_fold` is left out since it can't be overridden on stable Rust (the
+    // `Try` trait is unstable), so short-circuiting adapters still go through `next`.
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, T) -> B,
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.fold(init, f),
+            DynamicInclusiveRange::NonInclusive(r) => r.fold(init, f),
+        }
+    }
+
+    fn for_each<F>(self, f: F)
+    where
+        F: FnMut(T),
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.for_each(f),
+            DynamicInclusiveRange::NonInclusive(r) => r.for_each(f),
+        }
+    }
+
+    fn sum<S>(self) -> S
+    where
+        S: Sum<T>,
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.sum(),
+            DynamicInclusiveRange::NonInclusive(r) => r.sum(),
+        }
+    }
+
+    fn count(self) -> usize {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.count(),
+            DynamicInclusiveRange::NonInclusive(r) => r.count(),
+        }
+    }
+
+    fn last(self) -> Option<T> {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.last(),
+            DynamicInclusiveRange::NonInclusive(r) => r.last(),
+        }
+    }
+
+    fn min(self) -> Option<T>
+    where
+        T: Ord,
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.min(),
+            DynamicInclusiveRange::NonInclusive(r) => r.min(),
+        }
+    }
+
+    fn max(self) -> Option<T>
+    where
+        T: Ord,
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.max(),
+            DynamicInclusiveRange::NonInclusive(r) => r.max(),
+        }
+    }
+}
+
+impl<T> DoubleEndedIterator for DynamicInclusiveRange<T>
+where
+    Range<T>: DoubleEndedIterator<Item = T>,
+    RangeInclusive<T>: DoubleEndedIterator<Item = T>,
+{
+    fn next_back(&mut self) -> Option<T> {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.next_back(),
+            DynamicInclusiveRange::NonInclusive(r) => r.next_back(),
+        }
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<T> {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.nth_back(n),
+            DynamicInclusiveRange::NonInclusive(r) => r.nth_back(n),
+        }
+    }
+
+    fn rfold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, T) -> B,
+    {
+        match self {
+            DynamicInclusiveRange::Inclusive(r) => r.rfold(init, f),
+            DynamicInclusiveRange::NonInclusive(r) => r.rfold(init, f),
+        }
+    }
+}
+
+impl<T> FusedIterator for DynamicInclusiveRange<T>
+where
+    Range<T>: FusedIterator<Item = T>,
+    RangeInclusive<T>: FusedIterator<Item = T>,
+{
+}
+
+macro_rules! impl_exact_size {
+    ($($t:ty)*) => {
+        $(
+            impl ExactSizeIterator for DynamicInclusiveRange<$t> {}
+        )*
+    };
+}
+
+// The length of a dynamic range never exceeds `MAX - MIN + 1`, so it is exact only for the types
+// whose full inclusive range fits into `usize`.
+impl_exact_size!(u8 u16 i8 i16);
+#[cfg(target_pointer_width = "64")]
+impl_exact_size!(u32 i32);
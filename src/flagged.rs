@@ -0,0 +1,72 @@
+use crate::inclusive::inclusive_size_hint;
+use crate::{InclusiveRange, Integer};
+use std::iter::FusedIterator;
+
+/// An inclusive range that keeps a counter and an explicit `exhausted` flag, which is roughly what
+/// `RangeInclusive` does, minus the specializations of the standard library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlaggedRange<T> {
+    start: T,
+    end: T,
+    exhausted: bool,
+}
+
+impl<T: Integer> FlaggedRange<T> {
+    /// Initializes a range that yields every value from `from` to `inclusive_to`, both ends
+    /// included.
+    pub fn new(from: T, inclusive_to: T) -> Self {
+        FlaggedRange {
+            start: from,
+            end: inclusive_to,
+            exhausted: from > inclusive_to,
+        }
+    }
+}
+
+impl<T: Integer> Iterator for FlaggedRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.exhausted {
+            None
+        } else if self.start < self.end {
+            let value = self.start;
+            self.start = value.successor();
+            Some(value)
+        } else {
+            self.exhausted = true;
+            Some(self.start)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exhausted {
+            (0, Some(0))
+        } else {
+            inclusive_size_hint(self.start, self.end)
+        }
+    }
+}
+
+impl<T: Integer> DoubleEndedIterator for FlaggedRange<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.exhausted {
+            None
+        } else if self.start < self.end {
+            let value = self.end;
+            self.end = value.predecessor();
+            Some(value)
+        } else {
+            self.exhausted = true;
+            Some(self.end)
+        }
+    }
+}
+
+impl<T: Integer> FusedIterator for FlaggedRange<T> {}
+
+impl<T: Integer> InclusiveRange<T> for FlaggedRange<T> {
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        FlaggedRange::new(from, inclusive_to)
+    }
+}
@@ -0,0 +1,926 @@
+//! Criterion benchmarks of the range strategies.
+
+pub mod codegen;
+pub mod counters;
+pub mod environment;
+pub mod export;
+pub mod matrix;
+pub mod sanity;
+pub mod scenario;
+pub mod selection;
+pub mod stats;
+pub mod strategy;
+pub mod summary;
+pub mod workload;
+
+use self::counters::Probe;
+use self::export::{exact_len, Variant};
+use self::sanity::Rescale;
+use self::scenario::Scenario;
+use self::selection::{Mode, Options, Selection};
+use self::strategy::{
+    RangeStrategy, StepBy, SteppedStrategy, SteppedVisitor, Strategies, TypeVisitor, Visitor,
+};
+use self::workload::{Iteration, Workload};
+use crate::Integer;
+use criterion::{
+    black_box, BatchSize, Bencher, Criterion, Fun, ParameterizedBenchmark, Throughput,
+};
+use std::any::type_name;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// A test function that simply collapses the range by summing its elements.
+pub fn calc<T: Integer>(iter: impl Iterator<Item = T>) -> T {
+    iter.fold(T::ZERO, T::wrapping_add)
+}
+
+/// Sums the values with a plain `for` loop.
+pub fn sum_for<T: Integer>(iter: impl Iterator<Item = T>) -> T {
+    let mut acc = T::ZERO;
+    for i in iter {
+        acc = acc.wrapping_add(i);
+    }
+    acc
+}
+
+/// Sums the values with an explicit `while let Some(_) = iter.next()` loop.
+// Spelling out the loop that `for` desugars into is the whole point of this function.
+#[allow(clippy::while_let_on_iterator)]
+pub fn sum_while_let<T: Integer>(mut iter: impl Iterator<Item = T>) -> T {
+    let mut acc = T::ZERO;
+    while let Some(i) = iter.next() {
+        acc = acc.wrapping_add(i);
+    }
+    acc
+}
+
+/// Sums the values with [`Iterator::for_each`].
+pub fn sum_for_each<T: Integer>(iter: impl Iterator<Item = T>) -> T {
+    let mut acc = T::ZERO;
+    iter.for_each(|i| acc = acc.wrapping_add(i));
+    acc
+}
+
+/// A test function that collects the range into a vector, relying on its size hint to allocate.
+pub fn collect<T>(iter: impl Iterator<Item = T>) -> Vec<T> {
+    iter.collect()
+}
+
+/// Where a [`Span`] is located within the `u64` domain.
+#[derive(Clone, Copy)]
+enum Position {
+    /// The span starts at `1`.
+    Low,
+    /// The span starts in the middle of the domain.
+    Middle,
+    /// The span ends at `u64::MAX`.
+    Max,
+}
+
+/// An inclusive `u64` range of a given length located at a given position.
+#[derive(Clone, Copy)]
+struct Span {
+    /// The number of values in the span.
+    len: u64,
+    /// Where the span is located.
+    position: Position,
+}
+
+impl Span {
+    /// The lower bound of the span.
+    fn low(self) -> u64 {
+        match self.position {
+            Position::Low => 1,
+            Position::Middle => u64::MAX / 2,
+            Position::Max => u64::MAX - self.len.saturating_sub(1),
+        }
+    }
+
+    /// The inclusive upper bound of the span. It is below `low()` when the span is empty.
+    fn up(self) -> u64 {
+        self.low().wrapping_add(self.len).wrapping_sub(1)
+    }
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let position = match self.position {
+            Position::Low => "low",
+            Position::Middle => "mid",
+            Position::Max => "max",
+        };
+        write!(f, "{} len {}", position, self.len)
+    }
+}
+
+/// A helper function to prevent rust from optimizing out compile-time values.
+#[inline(never)]
+fn get_low_and_up<T: Integer>(low: T, up: T) -> impl FnMut() -> (T, T) {
+    move || (black_box(low), black_box(up))
+}
+
+/// Describes a benchmark of the range from `low` to `up` that consumes `elements` values, a single
+/// iteration of which over any range is returned by `probe`.
+fn variant<T: Integer>(
+    function: String,
+    strategy: &'static str,
+    workload: String,
+    low: T,
+    up: T,
+    elements: Option<u64>,
+    probe: impl Fn(T, T) -> Probe + 'static,
+) -> Variant {
+    Variant {
+        function,
+        parameter: None,
+        strategy,
+        workload,
+        integer: type_name::<T>(),
+        low: low.to_string(),
+        up: up.to_string(),
+        elements,
+        probe: probe(low, up),
+        rescale: Rescale::new(low, up, probe),
+    }
+}
+
+/// Creates a bencher that consumes the range built by `build` with `consume`, which is described
+/// by `strategy` and `workload`.
+fn make<T, I, B, F, R>(
+    label: &str,
+    strategy: &'static str,
+    workload: String,
+    low: T,
+    up: T,
+    build: B,
+    mut consume: F,
+) -> (Fun<()>, Variant)
+where
+    T: Integer,
+    I: Iterator,
+    B: Fn(T, T) -> I + Copy + 'static,
+    F: FnMut(I) -> R + Clone + 'static,
+{
+    let function = format!("{} {}", label, up);
+    let iteration = consume.clone();
+    let probe = move |low, up| {
+        let mut iteration = iteration.clone();
+        Probe::new(move || iteration(build(black_box(low), black_box(up))))
+    };
+    let fun = Fun::new(&function, move |b, &()| {
+        b.iter_batched(
+            get_low_and_up(low, up),
+            |(low, up)| consume(black_box(build(low, up))),
+            BatchSize::SmallInput,
+        );
+    });
+    let elements = exact_len(build(low, up));
+    (
+        fun,
+        variant(function, strategy, workload, low, up, elements, probe),
+    )
+}
+
+/// Creates a bencher that sums the range built by `build` in the `iteration` style.
+fn make_sum<T, I, B>(
+    label: &str,
+    strategy: &'static str,
+    workload: String,
+    low: T,
+    up: T,
+    iteration: Iteration,
+    build: B,
+) -> (Fun<()>, Variant)
+where
+    T: Integer,
+    I: Iterator<Item = T> + 'static,
+    B: Fn(T, T) -> I + Copy + 'static,
+{
+    match iteration {
+        Iteration::Fold => make(label, strategy, workload, low, up, build, calc),
+        Iteration::For => make(label, strategy, workload, low, up, build, sum_for),
+        Iteration::WhileLet => make(label, strategy, workload, low, up, build, sum_while_let),
+        Iteration::ForEach => make(label, strategy, workload, low, up, build, sum_for_each),
+    }
+}
+
+/// How a [`Benchers`] visitor consumes the ranges.
+#[derive(Clone, Copy)]
+enum Consumer {
+    /// Sums the range.
+    Sum(Iteration),
+    /// Sums the reversed range.
+    Rev(Iteration),
+    /// Collects the range with [`collect`].
+    Collect,
+    /// Sums every `n`-th element of the range.
+    Step(usize, Iteration),
+}
+
+impl Consumer {
+    /// The consumer of the workload `name` (as returned by [`Consumer::workload`] for the
+    /// [`Iteration::Fold`] style), if there is one.
+    fn from_workload(name: &str, iteration: Iteration) -> Option<Self> {
+        match name {
+            "sum" => Some(Consumer::Sum(iteration)),
+            "rev" => Some(Consumer::Rev(iteration)),
+            "collect" => Some(Consumer::Collect),
+            _ => match name.strip_prefix("step ").map(str::parse) {
+                Some(Ok(step)) => Some(Consumer::Step(step, iteration)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The name of the workload, followed by the iteration style unless it is the default one.
+    fn workload(self) -> String {
+        let (name, iteration) = match self {
+            Consumer::Sum(iteration) => ("sum".to_owned(), iteration),
+            Consumer::Rev(iteration) => ("rev".to_owned(), iteration),
+            Consumer::Collect => ("collect".to_owned(), Iteration::Fold),
+            Consumer::Step(step, iteration) => (format!("step {}", step), iteration),
+        };
+        match iteration {
+            Iteration::Fold => name,
+            _ => format!("{} {}", name, iteration.name()),
+        }
+    }
+}
+
+/// A visitor that creates a bencher for every selected strategy and upper bound.
+struct Benchers<'a, T> {
+    selection: &'a Selection,
+    consumer: Consumer,
+    low: T,
+    bounds: Vec<T>,
+    funs: Vec<(Fun<()>, Variant)>,
+}
+
+impl<'a, T: Integer> Benchers<'a, T> {
+    /// Creates the benchers of every selected strategy available for `T`.
+    fn collect(
+        selection: &'a Selection,
+        consumer: Consumer,
+        low: T,
+        bounds: Vec<T>,
+    ) -> Vec<(Fun<()>, Variant)>
+    where
+        T: Strategies,
+    {
+        if !selection.workload(&consumer.workload()) {
+            return Vec::new();
+        }
+        let mut benchers = Benchers {
+            selection,
+            consumer,
+            low,
+            bounds,
+            funs: Vec::new(),
+        };
+        match consumer {
+            Consumer::Step(..) => T::visit_stepped(&mut benchers),
+            _ => T::visit(&mut benchers),
+        }
+        benchers.funs
+    }
+
+    /// The label of the benchers of the strategy `name`.
+    fn label(&self, name: &str) -> String {
+        match self.consumer {
+            Consumer::Sum(Iteration::Fold) => name.to_owned(),
+            consumer => format!("{} {}", name, consumer.workload()),
+        }
+    }
+}
+
+impl<T: Integer> Visitor<T> for Benchers<'_, T> {
+    fn visit<S: RangeStrategy<T>>(&mut self) {
+        let iteration = match self.consumer {
+            // The range strategies are stepped through with `step_by`.
+            Consumer::Step(..) => return SteppedVisitor::visit::<StepBy<S>>(self),
+            Consumer::Sum(iteration) | Consumer::Rev(iteration) => iteration,
+            Consumer::Collect => Iteration::Fold,
+        };
+        if !self.selection.strategy(S::NAME) {
+            return;
+        }
+        let (low, label) = (self.low, self.label(S::NAME));
+        for &up in &self.bounds {
+            let workload = self.consumer.workload();
+            let fun = match self.consumer {
+                Consumer::Rev(_) => {
+                    let build = |low, up| S::build(low, up).rev();
+                    make_sum(&label, S::NAME, workload, low, up, iteration, build)
+                }
+                Consumer::Collect => make(&label, S::NAME, workload, low, up, S::build, collect),
+                _ => make_sum(&label, S::NAME, workload, low, up, iteration, S::build),
+            };
+            self.funs.push(fun);
+        }
+    }
+}
+
+impl<T: Integer> SteppedVisitor<T> for Benchers<'_, T> {
+    fn visit<S: SteppedStrategy<T>>(&mut self) {
+        let (step, iteration) = match self.consumer {
+            Consumer::Step(step, iteration) => (step, iteration),
+            _ => return,
+        };
+        if !self.selection.strategy(S::NAME) {
+            return;
+        }
+        let (low, label) = (self.low, self.label(S::NAME));
+        for &up in &self.bounds {
+            let workload = self.consumer.workload();
+            let build = move |low, up| S::build(low, up, step);
+            self.funs.push(make_sum(
+                &label,
+                S::NAME,
+                workload,
+                low,
+                up,
+                iteration,
+                build,
+            ));
+        }
+    }
+}
+
+/// How criterion takes the benchmarks of a group.
+enum Benchmarks {
+    /// Functions without parameters.
+    Functions(Vec<Fun<()>>),
+    /// Functions run with every span.
+    Parameterized(ParameterizedBenchmark<Span>),
+}
+
+/// A group of benchmarks along with the descriptions of every one of them.
+struct Group {
+    name: String,
+    benchmarks: Benchmarks,
+    variants: Vec<Variant>,
+}
+
+/// The benchmarks picked by a [`Selection`], which are either listed or run.
+struct Plan<'a> {
+    selection: &'a Selection,
+    groups: Vec<Group>,
+}
+
+impl<'a> Plan<'a> {
+    /// Creates every selected benchmark: the ones of the `scenarios`, or the built-in ones if there
+    /// are no scenarios.
+    fn new(selection: &'a Selection, scenarios: &[Scenario]) -> Self {
+        let mut plan = Plan {
+            selection,
+            groups: Vec::new(),
+        };
+        if scenarios.is_empty() {
+            ranges(&mut plan);
+            sweep(&mut plan);
+            workloads(&mut plan);
+            iteration(&mut plan);
+            collects(&mut plan);
+            steps(&mut plan);
+        } else {
+            for scenario in scenarios {
+                bench_scenario(&mut plan, scenario);
+            }
+        }
+        plan
+    }
+
+    /// Adds a group of functions, unless none of them is selected.
+    fn functions(&mut self, group: String, funs: Vec<(Fun<()>, Variant)>) {
+        if funs.is_empty() {
+            return;
+        }
+        let (funs, variants) = funs.into_iter().unzip();
+        self.groups.push(Group {
+            name: group,
+            benchmarks: Benchmarks::Functions(funs),
+            variants,
+        });
+    }
+
+    /// Every benchmark whose id contains `filter`, along with its id.
+    fn variants(&self, filter: Option<&str>) -> Vec<(String, &Variant)> {
+        self.groups
+            .iter()
+            .flat_map(|group| {
+                group
+                    .variants
+                    .iter()
+                    .map(move |variant| (variant.id(&group.name), variant))
+            })
+            .filter(|(id, _)| filter.is_none_or(|filter| id.contains(filter)))
+            .collect()
+    }
+
+    /// Prints the id and the parameters of every benchmark whose id contains `filter`.
+    fn list(&self, filter: Option<&str>) {
+        let benchmarks = self.variants(filter);
+        let width = benchmarks.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+        for (id, variant) in &benchmarks {
+            println!(
+                "{:<width$}  {:<5} {:<16} {:<10} {}..={}",
+                id,
+                variant.integer,
+                variant.strategy,
+                variant.workload,
+                variant.low,
+                variant.up,
+                width = width
+            );
+        }
+        println!("{} benchmarks", benchmarks.len());
+    }
+
+    /// Benches every group and registers its benchmarks for the export.
+    fn run(self, c: &mut Criterion) {
+        for group in self.groups {
+            export::register(&group.name, group.variants);
+            match group.benchmarks {
+                Benchmarks::Functions(funs) => {
+                    c.bench_functions(&group.name, funs, ());
+                }
+                Benchmarks::Parameterized(benchmark) => {
+                    c.bench(&group.name, benchmark);
+                }
+            }
+        }
+    }
+}
+
+/// The selection restricted to the strategies summed in closed form, which are the only ones that
+/// get through the full ranges of the wide types, or nothing if none of them is selected. The
+/// other strategies are benched over bounded spans by [`sweep`].
+fn closed_form(selection: &Selection) -> Option<Selection> {
+    let names: Vec<String> = strategy::closed_form()
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    selection.narrow(&names)
+}
+
+/// Benches all the ranges of type `T` that start at `T::MIN` and end right at or right before
+/// `T::MAX` (unless other bounds are selected), iterated both forwards and backwards, with the
+/// strategies summed in closed form. The type is benched if it is selected, or `by_default` if no
+/// type is selected.
+fn ranges_of<T: Strategies + FromStr>(plan: &mut Plan, by_default: bool) {
+    let selection = match closed_form(plan.selection) {
+        Some(selection) if selection.integer::<T>(by_default) => selection,
+        _ => return,
+    };
+    let low = T::MIN;
+    let bounds = selection.bounds(low, vec![T::MAX.predecessor(), T::MAX]);
+    let (sum, rev) = (
+        Consumer::Sum(Iteration::Fold),
+        Consumer::Rev(Iteration::Fold),
+    );
+    let mut funs = Benchers::collect(&selection, sum, low, bounds.clone());
+    funs.extend(Benchers::collect(&selection, rev, low, bounds));
+    plan.functions(format!("ranges {}", type_name::<T>()), funs);
+}
+
+/// Benches collecting the ranges of type `T` that start at `low` and end right at or right before
+/// `T::MAX` (unless other bounds are selected).
+fn collects_of<T: Strategies + FromStr>(plan: &mut Plan, low: T) {
+    let selection = plan.selection;
+    if !selection.integer::<T>(true) {
+        return;
+    }
+    let bounds = selection.bounds(low, vec![T::MAX.predecessor(), T::MAX]);
+    let funs = Benchers::collect(selection, Consumer::Collect, low, bounds);
+    plan.functions(format!("collect {}", type_name::<T>()), funs);
+}
+
+/// How many values the stepped ranges span at most. Stepping through a range is summed in closed
+/// form by none of the stepped strategies, so the ranges have to be short enough to be walked
+/// through.
+const STEPPED_SPAN: u128 = 1_000_000;
+
+/// Benches stepping through the ranges of type `T` that start [`STEPPED_SPAN`] values before
+/// `T::MAX` (or at `T::MIN` for the narrow types) and end right at or right before `T::MAX`
+/// (unless other bounds are selected).
+fn steps_of<T: Strategies + FromStr>(plan: &mut Plan, steps: &[usize]) {
+    let selection = plan.selection;
+    if !selection.integer::<T>(true) {
+        return;
+    }
+    let low = T::MIN.forward(T::MIN.distance(T::MAX).saturating_sub(STEPPED_SPAN));
+    let bounds = selection.bounds(low, vec![T::MAX.predecessor(), T::MAX]);
+    let mut funs = Vec::new();
+    for &step in steps {
+        funs.extend(Benchers::collect(
+            selection,
+            Consumer::Step(step, Iteration::Fold),
+            low,
+            bounds.clone(),
+        ));
+    }
+    plan.functions(format!("step {}", type_name::<T>()), funs);
+}
+
+/// The `u64` ranges a workload is benched over.
+enum WorkloadRanges {
+    /// The spans, which are the parameters of a single benchmark of every strategy.
+    Spans(Vec<Span>),
+    /// The ranges from `low` to every upper bound, each of which is a benchmark of its own.
+    Bounds { low: u64, bounds: Vec<u64> },
+}
+
+/// A visitor that benches a workload over the ranges with every selected strategy.
+struct WorkloadBenchers<'a, W> {
+    selection: &'a Selection,
+    workload: W,
+    ranges: WorkloadRanges,
+    benchmark: Option<ParameterizedBenchmark<Span>>,
+    funs: Vec<Fun<()>>,
+    variants: Vec<Variant>,
+}
+
+impl<'a, W: Workload> WorkloadBenchers<'a, W> {
+    /// Benches `workload` over `ranges` with every strategy of the `u64` ranges that `selection`
+    /// picks, and returns the benchmarks as the group `name`, unless none of them is selected.
+    fn group(
+        selection: &'a Selection,
+        name: String,
+        workload: W,
+        ranges: WorkloadRanges,
+    ) -> Option<Group> {
+        let mut benchers = WorkloadBenchers {
+            selection,
+            workload,
+            ranges,
+            benchmark: None,
+            funs: Vec::new(),
+            variants: Vec::new(),
+        };
+        u64::visit(&mut benchers);
+        let benchmarks = match benchers.benchmark {
+            Some(benchmark) => Benchmarks::Parameterized(
+                benchmark.throughput(|span| Throughput::Elements(span.len as u32)),
+            ),
+            None if !benchers.funs.is_empty() => Benchmarks::Functions(benchers.funs),
+            None => return None,
+        };
+        Some(Group {
+            name,
+            benchmarks,
+            variants: benchers.variants,
+        })
+    }
+}
+
+impl<W: Workload> Visitor<u64> for WorkloadBenchers<'_, W> {
+    fn visit<S: RangeStrategy<u64>>(&mut self) {
+        if !self.selection.strategy(S::NAME) {
+            return;
+        }
+        let name = self.workload.name();
+        match &self.ranges {
+            WorkloadRanges::Spans(spans) => {
+                let mut workload = self.workload.clone();
+                let fun = move |b: &mut Bencher, &span: &Span| {
+                    b.iter_batched(
+                        get_low_and_up(span.low(), span.up()),
+                        |(low, up)| workload.run(up, black_box(S::covering(low, up))),
+                        BatchSize::SmallInput,
+                    );
+                };
+                self.benchmark = Some(match self.benchmark.take() {
+                    None => ParameterizedBenchmark::new(S::NAME, fun, spans.clone()),
+                    Some(benchmark) => benchmark.with_function(S::NAME, fun),
+                });
+                for &span in spans {
+                    let (low, up) = (span.low(), span.up());
+                    let elements = exact_len(S::covering(low, up));
+                    let workload = self.workload.clone();
+                    let probe = move |low, up| {
+                        let mut workload = workload.clone();
+                        Probe::new(move || {
+                            workload.run(up, S::covering(black_box(low), black_box(up)))
+                        })
+                    };
+                    self.variants.push(Variant {
+                        parameter: Some(format!("{:?}", span)),
+                        ..variant(
+                            S::NAME.to_owned(),
+                            S::NAME,
+                            name.into(),
+                            low,
+                            up,
+                            elements,
+                            probe,
+                        )
+                    });
+                }
+            }
+            &WorkloadRanges::Bounds { low, ref bounds } => {
+                let label = format!("{} {}", S::NAME, name);
+                for &up in bounds {
+                    let mut workload = self.workload.clone();
+                    let consume = move |r| workload.run(up, r);
+                    let (fun, variant) =
+                        make(&label, S::NAME, name.into(), low, up, S::build, consume);
+                    self.funs.push(fun);
+                    self.variants.push(variant);
+                }
+            }
+        }
+    }
+}
+
+/// Benches running `workload` over the selected `u64` spans with every selected range strategy.
+///
+/// Unlike everywhere else, the ranges are built with [`RangeStrategy::covering`], so the
+/// non-inclusive range yields the whole span (and matches the throughput of the span) unless the
+/// span ends at `u64::MAX`, where it yields one element less.
+fn bench_workload<W: Workload>(plan: &mut Plan, group: String, workload: W, spans: Vec<Span>) {
+    let selection = plan.selection;
+    if !selection.integer::<u64>(true) || !selection.workload(workload.name()) {
+        return;
+    }
+    let spans: Vec<Span> = spans
+        .into_iter()
+        .filter(|span| selection.up(span.up()))
+        .collect();
+    if spans.is_empty() {
+        return;
+    }
+    let ranges = WorkloadRanges::Spans(spans);
+    plan.groups
+        .extend(WorkloadBenchers::group(selection, group, workload, ranges));
+}
+
+/// Returns the spans of the given lengths at every position.
+fn spans(lengths: &[u64]) -> Vec<Span> {
+    let positions = [Position::Low, Position::Middle, Position::Max];
+    positions
+        .iter()
+        .flat_map(|&position| lengths.iter().map(move |&len| Span { len, position }))
+        .collect()
+}
+
+/// Benches summing the `u64` ranges of a few realistic lengths, located near zero, in the middle
+/// and at the upper boundary of the domain.
+fn sweep(plan: &mut Plan) {
+    bench_workload(
+        plan,
+        "sweep".to_owned(),
+        workload::Sum,
+        spans(&[0, 1, 10, 1_000, 1_000_000]),
+    );
+}
+
+/// Benches every workload over the `u64` ranges.
+fn workloads(plan: &mut Plan) {
+    struct Bench<'a, 'b>(&'a mut Plan<'b>);
+
+    impl workload::Visitor for Bench<'_, '_> {
+        fn visit<W: Workload>(&mut self, workload: W) {
+            let group = format!("workload {}", workload.name());
+            bench_workload(self.0, group, workload, spans(&[1_000, 1_000_000]));
+        }
+    }
+
+    workload::visit(&mut Bench(plan));
+}
+
+/// Benches summing the `u64` ranges with external (`for`, `while let`) and internal (`for_each`,
+/// `fold`) iteration.
+fn iteration(plan: &mut Plan) {
+    fn bench<W: Workload>(plan: &mut Plan, style: &str, workload: W) {
+        let group = format!("iteration {}", style);
+        bench_workload(plan, group, workload, spans(&[1_000, 1_000_000]));
+    }
+
+    bench(plan, "for", workload::ForLoop);
+    bench(plan, "while-let", workload::WhileLet);
+    bench(plan, "for-each", workload::ForEach);
+    bench(plan, "fold", workload::Sum);
+}
+
+/// Benches summing the `u64` ranges that start at `1` and end right at or right before `u64::MAX`
+/// (unless other bounds are selected) with the strategies summed in closed form, which are the
+/// benchmarks the crate started with and keep their original ids.
+fn ranges_from_one(plan: &mut Plan) {
+    let selection = match closed_form(plan.selection) {
+        Some(selection) if selection.integer::<u64>(true) => selection,
+        _ => return,
+    };
+    let bounds = selection.bounds(1, vec![u64::MAX - 1, u64::MAX]);
+    let funs = Benchers::collect(&selection, Consumer::Sum(Iteration::Fold), 1, bounds);
+    plan.functions("ranges".to_owned(), funs);
+}
+
+/// Benches summing the ranges of the integer types: the original `u64` ranges, then the full
+/// ranges of the ones of 8 to 128 bits by default, and of any other supported type if it is
+/// selected.
+fn ranges(plan: &mut Plan) {
+    ranges_from_one(plan);
+    ranges_of::<u8>(plan, true);
+    ranges_of::<u16>(plan, true);
+    ranges_of::<u32>(plan, true);
+    ranges_of::<u64>(plan, true);
+    ranges_of::<usize>(plan, true);
+    ranges_of::<i32>(plan, true);
+    ranges_of::<i64>(plan, true);
+    ranges_of::<u128>(plan, true);
+    ranges_of::<i8>(plan, false);
+    ranges_of::<i16>(plan, false);
+    ranges_of::<i128>(plan, false);
+    ranges_of::<isize>(plan, false);
+}
+
+/// Benches collecting the ranges into vectors.
+fn collects(plan: &mut Plan) {
+    collects_of::<u16>(plan, u16::MAX - 4095);
+    collects_of::<u32>(plan, u32::MAX - 4095);
+    collects_of::<u64>(plan, u64::MAX - 4095);
+}
+
+/// Benches stepping through the ranges with a few different steps.
+fn steps(plan: &mut Plan) {
+    steps_of::<u32>(plan, &[1, 3, 64]);
+    steps_of::<u64>(plan, &[1, 3, 64]);
+}
+
+/// A visitor that benches a scenario consumed by a [`Consumer`] when it visits the integer type of
+/// the scenario.
+struct ScenarioBenchers<'p, 'a> {
+    plan: &'p mut Plan<'a>,
+    scenario: &'p Scenario,
+    consumer: Consumer,
+}
+
+impl TypeVisitor for ScenarioBenchers<'_, '_> {
+    fn visit<T: Strategies + FromStr>(&mut self) {
+        let scenario = self.scenario;
+        if type_name::<T>() != scenario.integer || !self.plan.selection.integer::<T>(true) {
+            return;
+        }
+        let selection = match self.plan.selection.narrow(&scenario.strategies) {
+            Some(selection) => selection,
+            None => return,
+        };
+        let (low, bounds) = match scenario.bounds::<T>() {
+            Ok((low, bounds)) => (low, bounds),
+            Err(_) => return,
+        };
+        let bounds: Vec<T> = bounds.into_iter().filter(|&up| selection.up(up)).collect();
+        let funs = Benchers::collect(&selection, self.consumer, low, bounds);
+        self.plan.functions(scenario.name.clone(), funs);
+    }
+}
+
+/// Benches a scenario of a `u64` workload.
+fn bench_workload_scenario(plan: &mut Plan, scenario: &Scenario) {
+    struct Find<'a> {
+        selection: &'a Selection,
+        scenario: &'a Scenario,
+        low: u64,
+        bounds: Vec<u64>,
+        group: Option<Group>,
+    }
+
+    impl workload::Visitor for Find<'_> {
+        fn visit<W: Workload>(&mut self, workload: W) {
+            if workload.name() != self.scenario.workload {
+                return;
+            }
+            let ranges = WorkloadRanges::Bounds {
+                low: self.low,
+                bounds: self.bounds.clone(),
+            };
+            let name = self.scenario.name.clone();
+            self.group = WorkloadBenchers::group(self.selection, name, workload, ranges);
+        }
+    }
+
+    let selection = match plan.selection.narrow(&scenario.strategies) {
+        Some(selection) => selection,
+        None => return,
+    };
+    if !selection.integer::<u64>(true) || !selection.workload(&scenario.workload) {
+        return;
+    }
+    let (low, bounds) = match scenario.bounds::<u64>() {
+        Ok((low, bounds)) => (low, bounds),
+        Err(_) => return,
+    };
+    let mut find = Find {
+        selection: &selection,
+        scenario,
+        low,
+        bounds: bounds.into_iter().filter(|&up| selection.up(up)).collect(),
+        group: None,
+    };
+    workload::visit(&mut find);
+    plan.groups.extend(find.group);
+}
+
+/// Benches a scenario, whose integer type and workload are known to be valid.
+fn bench_scenario(plan: &mut Plan, scenario: &Scenario) {
+    match Consumer::from_workload(&scenario.workload, scenario.iteration) {
+        Some(consumer) => strategy::visit_types(&mut ScenarioBenchers {
+            plan,
+            scenario,
+            consumer,
+        }),
+        None => bench_workload_scenario(plan, scenario),
+    }
+}
+
+/// Loads the scenario files of `options`, exiting if any of them is invalid.
+fn load_scenarios(options: &Options) -> Vec<Scenario> {
+    let mut scenarios = Vec::new();
+    for path in &options.scenarios {
+        match scenario::load(path) {
+            Ok(loaded) => scenarios.extend(loaded),
+            Err(error) => {
+                eprintln!("Invalid scenarios:\n{}", error);
+                process::exit(1);
+            }
+        }
+    }
+    scenarios
+}
+
+/// Loads the exported results from `path`, or from the latest export if there is no path, exiting
+/// if they can't be loaded.
+fn load_results(path: Option<&Path>) -> export::Export {
+    let loaded = match path {
+        Some(path) => export::load(path),
+        None => export::latest(&export::results_dir()).and_then(|latest| match latest {
+            Some(path) => export::load(&path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "there are no exported results yet",
+            )),
+        }),
+    };
+    loaded.unwrap_or_else(|error| {
+        eprintln!("Failed to load the results: {}", error);
+        process::exit(1);
+    })
+}
+
+/// Creates the benchmarks selected by `options`.
+fn plan(options: &Options) -> Plan<'_> {
+    Plan::new(&options.selection, &load_scenarios(options))
+}
+
+/// Does what the [`Mode`] of `options` asks for, which is running the selected benchmarks and
+/// exporting their results by default.
+pub fn run(options: &Options) {
+    let filter = options.filter.as_deref();
+    match &options.mode {
+        Mode::Bench => bench(plan(options), options),
+        Mode::List => plan(options).list(filter),
+        Mode::Sanity => {
+            // Fails when any of the benchmarks doesn't scale linearly with the length of the range.
+            if sanity::run(&plan(options).variants(filter)) > 0 {
+                process::exit(1);
+            }
+        }
+        Mode::Codegen => {
+            if let Err(error) = codegen::run(&export::target_dir().join("codegen")) {
+                eprintln!("Failed to dump the code: {}", error);
+                process::exit(1);
+            }
+        }
+        Mode::Summary(path) => summary::print(&load_results(path.as_deref())),
+        Mode::Pairwise(path) => summary::pairwise(&load_results(path.as_deref())),
+        Mode::Compare { baseline, current } => {
+            let baseline = load_results(Some(baseline));
+            summary::compare(&baseline, &load_results(current.as_deref()));
+        }
+    }
+}
+
+/// Runs the benchmarks of `plan` with the criterion configured by `options`, and exports their
+/// results.
+fn bench(plan: Plan, options: &Options) {
+    environment::Environment::capture().print();
+    let started = SystemTime::now();
+    let mut criterion = options.criterion();
+    plan.run(&mut criterion);
+    criterion.final_summary();
+    match export::run(started) {
+        Ok(Some((export, path))) => {
+            println!();
+            summary::print_counters(&export);
+            summary::print(&export);
+            println!("The results are exported to {}", path.display());
+        }
+        Ok(None) => {}
+        Err(error) => eprintln!("Failed to export the results: {}", error),
+    }
+}
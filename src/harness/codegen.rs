@@ -0,0 +1,280 @@
+//! Assembly and LLVM IR of [`calc`] instantiated for every range strategy.
+//!
+//! Every strategy gets an `#[inline(never)]`, `#[no_mangle]` entry point that sums a `u64` range, so
+//! its code can be found by the symbol name in the output of `rustc --emit=asm,llvm-ir`.
+
+use super::calc;
+use super::strategy::{self, RangeStrategy};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A monomorphic entry point that sums the range built by a strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct EntryPoint {
+    /// The name of the strategy.
+    pub strategy: &'static str,
+    /// The unmangled symbol of the entry point.
+    pub symbol: &'static str,
+}
+
+macro_rules! entry_points {
+    ($($strategy:ident => $symbol:ident,)*) => {
+        $(
+            /// Sums the `u64` range built by the strategy of the same name.
+            #[inline(never)]
+            #[no_mangle]
+            pub fn $symbol(low: u64, up: u64) -> u64 {
+                calc(<strategy::$strategy as RangeStrategy<u64>>::build(low, up))
+            }
+        )*
+
+        /// The entry points of every strategy available for `u64`.
+        pub const ENTRY_POINTS: &[EntryPoint] = &[
+            $(
+                EntryPoint {
+                    strategy: <strategy::$strategy as RangeStrategy<u64>>::NAME,
+                    symbol: stringify!($symbol),
+                },
+            )*
+        ];
+    };
+}
+
+entry_points! {
+    NonInclusive => range_perf_calc_non_inclusive,
+    Inclusive => range_perf_calc_inclusive,
+    Dynamic => range_perf_calc_dynamic,
+    Flagged => range_perf_calc_flagged,
+    DoWhile => range_perf_calc_do_while,
+    OptionEnd => range_perf_calc_option_end,
+    Wide => range_perf_calc_wide,
+}
+
+/// The assembly and LLVM IR of the whole library.
+#[derive(Debug, Clone)]
+pub struct Artifacts {
+    /// The assembly.
+    pub asm: String,
+    /// The LLVM IR.
+    pub ir: String,
+}
+
+/// Builds the library in release mode with `--emit=asm,llvm-ir` into `target_dir`, which should
+/// differ from the regular target directory so the usual artifacts are not rebuilt every time.
+///
+/// A single codegen unit is used, so the output comes in one file of each kind.
+pub fn emit(target_dir: &Path) -> io::Result<Artifacts> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["rustc", "--release", "--lib", "--target-dir"])
+        .arg(target_dir)
+        .args(["--", "--emit=asm,llvm-ir", "-C", "codegen-units=1"])
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("cargo rustc failed: {}", status)));
+    }
+    let deps = target_dir.join("release").join("deps");
+    Ok(Artifacts {
+        asm: fs::read_to_string(newest(&deps, "s")?)?,
+        ir: fs::read_to_string(newest(&deps, "ll")?)?,
+    })
+}
+
+/// Returns the most recently modified library file with the given extension in `dir`.
+fn newest(dir: &Path, extension: &str) -> io::Result<PathBuf> {
+    let mut newest = None;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_ours = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("range_perf-"));
+        if !is_ours || path.extension().is_none_or(|ext| ext != extension) {
+            continue;
+        }
+        let modified = fs::metadata(&path)?.modified()?;
+        if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            newest = Some((modified, path));
+        }
+    }
+    newest.map(|(_, path)| path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no .{} file in {}", extension, dir.display()),
+        )
+    })
+}
+
+/// Extracts the assembly of the function `symbol`, from its label up to the end of the function.
+pub fn function_asm(asm: &str, symbol: &str) -> Option<String> {
+    let label = format!("{}:", symbol);
+    // Mach-O prefixes the symbols with an underscore.
+    let prefixed = format!("_{}:", symbol);
+    let mut lines = asm
+        .lines()
+        .skip_while(|line| *line != label && *line != prefixed);
+    let first = lines.next()?;
+    let mut function = vec![first];
+    function.extend(lines.take_while(|line| !line.starts_with(".Lfunc_end")));
+    Some(function.join("\n") + "\n")
+}
+
+/// Extracts the LLVM IR of the function `symbol`, from its `define` up to the closing brace.
+pub fn function_ir(ir: &str, symbol: &str) -> Option<String> {
+    let needle = format!("@{}(", symbol);
+    let mut lines = ir
+        .lines()
+        .skip_while(|line| !(line.starts_with("define") && line.contains(&needle)));
+    let first = lines.next()?;
+    let mut function = vec![first];
+    for line in lines {
+        function.push(line);
+        if line == "}" {
+            break;
+        }
+    }
+    Some(function.join("\n") + "\n")
+}
+
+/// Statistics of the assembly of a single function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AsmStats {
+    /// The number of instructions.
+    pub instructions: usize,
+    /// The number of loops, i.e. of distinct targets of backward jumps.
+    pub loops: usize,
+    /// The number of conditional branches inside the loop bodies, not counting the jumps that
+    /// close the loops.
+    pub loop_branches: usize,
+    /// Whether the function uses vector (SIMD) registers.
+    pub vectorized: bool,
+}
+
+/// A line of assembly, stripped of the comments.
+enum Line<'a> {
+    Label(&'a str),
+    Instruction {
+        mnemonic: &'a str,
+        operands: &'a str,
+    },
+}
+
+fn parse_line(line: &str) -> Option<Line<'_>> {
+    // Comments start with `# ` on x86-64 and with `//` on AArch64, where `#` marks immediates.
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let line = line.split("# ").next().unwrap_or("");
+    let line = line.split("//").next().unwrap_or("").trim();
+    if line.is_empty() || line.starts_with('.') && !line.ends_with(':') {
+        None
+    } else if let Some(label) = line.strip_suffix(':') {
+        Some(Line::Label(label))
+    } else {
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let mnemonic = parts.next().unwrap_or("");
+        let operands = parts.next().unwrap_or("").trim();
+        Some(Line::Instruction { mnemonic, operands })
+    }
+}
+
+/// Whether `mnemonic` is a conditional branch on x86-64 or AArch64.
+fn is_conditional_branch(mnemonic: &str) -> bool {
+    (mnemonic.starts_with('j') && !mnemonic.starts_with("jmp"))
+        || mnemonic.starts_with("b.")
+        || ["cbz", "cbnz", "tbz", "tbnz"].contains(&mnemonic)
+}
+
+/// Whether `mnemonic` is a branch of any kind on x86-64 or AArch64.
+fn is_branch(mnemonic: &str) -> bool {
+    is_conditional_branch(mnemonic) || mnemonic.starts_with("jmp") || mnemonic == "b"
+}
+
+/// Analyzes the assembly of a single function, as returned by [`function_asm`].
+///
+/// The analysis is a heuristic: every backward jump is taken for a jump that closes a loop, whose
+/// body spans from the target label to the jump itself.
+pub fn analyze(function: &str) -> AsmStats {
+    let lines: Vec<Line> = function.lines().filter_map(parse_line).collect();
+    let label_at = |name: &str| {
+        lines
+            .iter()
+            .position(|line| matches!(line, Line::Label(label) if *label == name))
+    };
+    let mut stats = AsmStats::default();
+    // The loop bodies as `(target label, closing jump)` index pairs.
+    let mut loops = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let (mnemonic, operands) = match line {
+            Line::Instruction { mnemonic, operands } => (*mnemonic, *operands),
+            Line::Label(_) => continue,
+        };
+        stats.instructions += 1;
+        stats.vectorized |= operands.contains("%xmm")
+            || operands.contains("%ymm")
+            || operands.contains("%zmm")
+            || operands.contains(".2d");
+        if !is_branch(mnemonic) {
+            continue;
+        }
+        let target = operands.rsplit(',').next().unwrap_or("").trim();
+        match label_at(target) {
+            Some(start) if start < index => loops.push((start, index)),
+            _ => {}
+        }
+    }
+
+    let mut headers: Vec<usize> = loops.iter().map(|&(start, _)| start).collect();
+    headers.sort_unstable();
+    headers.dedup();
+    stats.loops = headers.len();
+    stats.loop_branches = lines
+        .iter()
+        .enumerate()
+        .filter(|(index, line)| {
+            let in_loop = loops
+                .iter()
+                .any(|&(start, end)| start < *index && *index <= end);
+            let closes_loop = loops.iter().any(|&(_, end)| end == *index);
+            in_loop
+                && !closes_loop
+                && matches!(line, Line::Instruction { mnemonic, .. } if is_conditional_branch(mnemonic))
+        })
+        .count();
+    stats
+}
+
+/// Emits the code of every entry point into `<target_dir>/<strategy>.s` and `.ll` and prints the
+/// statistics of each.
+pub fn run(target_dir: &Path) -> io::Result<()> {
+    let artifacts = emit(target_dir)?;
+    println!(
+        "{:<16} {:>12} {:>6} {:>14} {:>11}",
+        "strategy", "instructions", "loops", "loop branches", "vectorized"
+    );
+    for entry in ENTRY_POINTS {
+        let asm = function_asm(&artifacts.asm, entry.symbol);
+        let ir = function_ir(&artifacts.ir, entry.symbol);
+        let (asm, ir) = match (asm, ir) {
+            (Some(asm), Some(ir)) => (asm, ir),
+            _ => {
+                println!("{:<16} not found in the output", entry.strategy);
+                continue;
+            }
+        };
+        fs::write(target_dir.join(format!("{}.s", entry.strategy)), &asm)?;
+        fs::write(target_dir.join(format!("{}.ll", entry.strategy)), &ir)?;
+        let stats = analyze(&asm);
+        println!(
+            "{:<16} {:>12} {:>6} {:>14} {:>11}",
+            entry.strategy, stats.instructions, stats.loops, stats.loop_branches, stats.vectorized
+        );
+    }
+    println!("The code of every strategy is in {}", target_dir.display());
+    Ok(())
+}
@@ -0,0 +1,282 @@
+//! Hardware performance counters per iteration of every benchmark.
+//!
+//! Once criterion is done, every benchmark measured during the run is repeated outside of criterion
+//! with the instructions, cycles, branches and branch misses counted by the CPU. On Linux the
+//! counters are opened with `perf_event_open`; elsewhere, and wherever the kernel refuses to open
+//! them (which is common in containers and virtual machines), they are simply not reported.
+
+use criterion::black_box;
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// The minimum time the counters of a single benchmark are collected for.
+const MEASUREMENT_TIME: Duration = Duration::from_millis(10);
+
+/// A single iteration of a benchmark that can be run outside of criterion.
+#[derive(Clone)]
+pub struct Probe(Rc<RefCell<dyn FnMut()>>);
+
+impl Probe {
+    /// Wraps a single iteration of a benchmark, whose result is black-boxed.
+    pub fn new<R>(mut iteration: impl FnMut() -> R + 'static) -> Self {
+        Probe(Rc::new(RefCell::new(move || {
+            black_box(iteration());
+        })))
+    }
+
+    /// Runs a single iteration.
+    pub fn run(&self) {
+        (self.0.borrow_mut())()
+    }
+}
+
+impl fmt::Debug for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Probe")
+    }
+}
+
+/// The hardware events counted per iteration, each of which is missing if the CPU or the kernel
+/// doesn't count it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Counts {
+    /// The instructions retired.
+    pub instructions: Option<f64>,
+    /// The CPU cycles.
+    pub cycles: Option<f64>,
+    /// The branch instructions retired.
+    pub branches: Option<f64>,
+    /// The mispredicted branches.
+    pub branch_misses: Option<f64>,
+}
+
+/// The hardware counters of the current thread.
+pub struct Counters {
+    instructions: Option<sys::Counter>,
+    cycles: Option<sys::Counter>,
+    branches: Option<sys::Counter>,
+    branch_misses: Option<sys::Counter>,
+}
+
+impl Counters {
+    /// Opens every counter available, and fails if none is.
+    pub fn open() -> io::Result<Self> {
+        let mut error = None;
+        let mut open = |event| sys::Counter::open(event).map_err(|e| error = Some(e)).ok();
+        let counters = Counters {
+            instructions: open(sys::Event::Instructions),
+            cycles: open(sys::Event::Cycles),
+            branches: open(sys::Event::Branches),
+            branch_misses: open(sys::Event::BranchMisses),
+        };
+        match error {
+            Some(error) if counters.all().all(|counter| counter.is_none()) => Err(error),
+            _ => Ok(counters),
+        }
+    }
+
+    fn all(&self) -> impl Iterator<Item = &Option<sys::Counter>> {
+        vec![
+            &self.instructions,
+            &self.cycles,
+            &self.branches,
+            &self.branch_misses,
+        ]
+        .into_iter()
+    }
+
+    /// Counts the events of `probe` per iteration, running it for at least
+    /// [`MEASUREMENT_TIME`].
+    pub fn measure(&self, probe: &Probe) -> io::Result<Counts> {
+        // Warms the caches and the branch predictor up.
+        probe.run();
+        for counter in self.all().flatten() {
+            counter.reset()?;
+        }
+        for counter in self.all().flatten() {
+            counter.enable()?;
+        }
+        let start = Instant::now();
+        let mut iterations = 0u64;
+        let mut batch = 1u64;
+        while start.elapsed() < MEASUREMENT_TIME {
+            for _ in 0..batch {
+                probe.run();
+            }
+            iterations += batch;
+            batch *= 2;
+        }
+        for counter in self.all().flatten() {
+            counter.disable()?;
+        }
+        let per_iteration = |counter: &Option<sys::Counter>| -> io::Result<Option<f64>> {
+            Ok(match counter {
+                Some(counter) => counter.read()?.map(|count| count / iterations as f64),
+                None => None,
+            })
+        };
+        Ok(Counts {
+            instructions: per_iteration(&self.instructions)?,
+            cycles: per_iteration(&self.cycles)?,
+            branches: per_iteration(&self.branches)?,
+            branch_misses: per_iteration(&self.branch_misses)?,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::mem;
+    use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 2;
+    const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+    /// The flags of the `perf_event_attr` bit field.
+    const DISABLED: u64 = 1;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+
+    /// The first version of `struct perf_event_attr`, which every kernel accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    /// The hardware events that are counted.
+    #[derive(Clone, Copy)]
+    pub enum Event {
+        Cycles = 0,
+        Instructions = 1,
+        Branches = 4,
+        BranchMisses = 5,
+    }
+
+    /// A counter of a hardware event in the user space of the current thread.
+    pub struct Counter(File);
+
+    impl Counter {
+        pub fn open(event: Event) -> io::Result<Self> {
+            let attr = PerfEventAttr {
+                kind: PERF_TYPE_HARDWARE,
+                size: mem::size_of::<PerfEventAttr>() as u32,
+                config: event as u64,
+                read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+                flags: DISABLED | EXCLUDE_KERNEL | EXCLUDE_HV,
+                ..PerfEventAttr::default()
+            };
+            // The current thread on any CPU, not in a group.
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    0,
+                    -1,
+                    -1,
+                    0,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Counter(unsafe { File::from_raw_fd(fd as RawFd) }))
+        }
+
+        fn ioctl(&self, request: libc::c_ulong) -> io::Result<()> {
+            if unsafe { libc::ioctl(self.0.as_raw_fd(), request as _, 0) } < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+
+        pub fn reset(&self) -> io::Result<()> {
+            self.ioctl(PERF_EVENT_IOC_RESET)
+        }
+
+        pub fn enable(&self) -> io::Result<()> {
+            self.ioctl(PERF_EVENT_IOC_ENABLE)
+        }
+
+        pub fn disable(&self) -> io::Result<()> {
+            self.ioctl(PERF_EVENT_IOC_DISABLE)
+        }
+
+        /// Reads the count, scaled up if the counter was multiplexed with others, or nothing if it
+        /// never got to run.
+        pub fn read(&self) -> io::Result<Option<f64>> {
+            let mut buffer = [0u8; 24];
+            (&self.0).read_exact(&mut buffer)?;
+            let word = |i: usize| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&buffer[i * 8..i * 8 + 8]);
+                u64::from_ne_bytes(bytes) as f64
+            };
+            let (value, enabled, running) = (word(0), word(1), word(2));
+            Ok(if running == 0. {
+                None
+            } else {
+                Some(value * enabled / running)
+            })
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    #[derive(Clone, Copy)]
+    pub enum Event {
+        Cycles,
+        Instructions,
+        Branches,
+        BranchMisses,
+    }
+
+    /// Never opened outside of Linux.
+    pub enum Counter {}
+
+    impl Counter {
+        pub fn open(_event: Event) -> io::Result<Self> {
+            Err(io::Error::other(
+                "hardware counters are only supported on Linux",
+            ))
+        }
+
+        pub fn reset(&self) -> io::Result<()> {
+            match *self {}
+        }
+
+        pub fn enable(&self) -> io::Result<()> {
+            match *self {}
+        }
+
+        pub fn disable(&self) -> io::Result<()> {
+            match *self {}
+        }
+
+        pub fn read(&self) -> io::Result<Option<f64>> {
+            match *self {}
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! The environment the benchmarks run in: how they were built and what they run on.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// The build of the benchmarks and the machine they run on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    /// The version of rustc the benchmarks were built with.
+    pub rustc: String,
+    /// The version of LLVM of that rustc.
+    pub llvm: String,
+    /// The target triple.
+    pub target: String,
+    /// The target features enabled at build time.
+    pub target_features: Vec<String>,
+    /// The cargo profile of the build.
+    pub profile: String,
+    /// The optimization level of the build.
+    pub opt_level: String,
+    /// The flags passed to rustc on top of the profile.
+    pub rustflags: String,
+    /// The model of the CPU, from `/proc/cpuinfo`.
+    pub cpu: Option<String>,
+    /// The CPU frequency governor, from sysfs.
+    pub governor: Option<String>,
+    /// Whether turbo boost is enabled, from sysfs.
+    pub turbo: Option<bool>,
+    /// The kernel version.
+    pub kernel: Option<String>,
+}
+
+/// Reads a sysfs or procfs file with a single value.
+fn read_value(path: &str) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Returns the model name of the first CPU.
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    // `model name` on x86, `Model` or `Hardware` on ARM.
+    cpuinfo
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            Some((parts.next()?.trim(), parts.next()?.trim()))
+        })
+        .find(|(key, _)| ["model name", "Model", "Hardware"].contains(key))
+        .map(|(_, value)| value.to_owned())
+}
+
+/// Whether turbo boost is enabled, by the `intel_pstate` driver or the generic cpufreq one.
+fn turbo() -> Option<bool> {
+    if let Some(no_turbo) = read_value("/sys/devices/system/cpu/intel_pstate/no_turbo") {
+        return Some(no_turbo == "0");
+    }
+    read_value("/sys/devices/system/cpu/cpufreq/boost").map(|boost| boost == "1")
+}
+
+impl Environment {
+    /// Captures the environment of the running benchmarks.
+    pub fn capture() -> Self {
+        let features = env!("RANGE_PERF_TARGET_FEATURES");
+        Environment {
+            rustc: env!("RANGE_PERF_RUSTC").to_owned(),
+            llvm: env!("RANGE_PERF_LLVM").to_owned(),
+            target: env!("RANGE_PERF_TARGET").to_owned(),
+            target_features: features
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            profile: env!("RANGE_PERF_PROFILE").to_owned(),
+            opt_level: env!("RANGE_PERF_OPT_LEVEL").to_owned(),
+            rustflags: env!("RANGE_PERF_RUSTFLAGS").to_owned(),
+            cpu: cpu_model(),
+            governor: read_value("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            turbo: turbo(),
+            kernel: read_value("/proc/sys/kernel/osrelease"),
+        }
+    }
+
+    /// Describes what in the environment is likely to make the timings unreliable.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.profile != "release" {
+            warnings.push(format!(
+                "the benchmarks are built with the {} profile rather than the release one",
+                self.profile
+            ));
+        }
+        if let Some(governor) = self.governor.as_ref().filter(|g| *g != "performance") {
+            warnings.push(format!(
+                "the CPU frequency governor is {} rather than performance, the timings are \
+                 likely to be noisy",
+                governor
+            ));
+        }
+        if self.turbo == Some(true) {
+            warnings.push(
+                "turbo boost is enabled, the timings depend on the temperature of the CPU"
+                    .to_owned(),
+            );
+        }
+        warnings
+    }
+
+    /// Prints the environment along with the warnings about it.
+    pub fn print(&self) {
+        let unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "unknown".into());
+        println!(
+            "{} (LLVM {}), {} profile with opt-level {}, {}",
+            self.rustc, self.llvm, self.profile, self.opt_level, self.target
+        );
+        println!(
+            "{}, governor {}, turbo {}, kernel {}",
+            unknown(&self.cpu),
+            unknown(&self.governor),
+            match self.turbo {
+                Some(true) => "on",
+                Some(false) => "off",
+                None => "unknown",
+            },
+            unknown(&self.kernel)
+        );
+        for warning in self.warnings() {
+            println!("warning: {}", warning);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Machine-readable export of the benchmark results.
+//!
+//! Every benchmark registers its parameters when it is created. Once criterion is done, the
+//! estimates it has saved under `target/criterion` for the benchmarks measured during the run are
+//! gathered into a single JSON and a single CSV file under `target/range-perf`, along with the
+//! hardware [`counters`](super::counters) of every benchmark. The target directory is the one set
+//! by `CARGO_TARGET_DIR`, if any.
+
+use super::counters::{Counters, Probe};
+use super::environment::Environment;
+use super::sanity::Rescale;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The parameters of a single benchmark.
+#[derive(Debug, Clone)]
+pub struct Variant {
+    /// The id of the benchmark within its group, without the parameter.
+    pub function: String,
+    /// The parameter of a parameterized benchmark, as shown by criterion.
+    pub parameter: Option<String>,
+    /// The name of the range strategy.
+    pub strategy: &'static str,
+    /// What is done with the range: the name of the workload or of the way it is consumed.
+    pub workload: String,
+    /// The integer type of the range.
+    pub integer: &'static str,
+    /// The lower bound of the range.
+    pub low: String,
+    /// The upper bound of the range, inclusive unless the strategy states otherwise.
+    pub up: String,
+    /// The number of values consumed by a single iteration, if it fits into `u64`.
+    pub elements: Option<u64>,
+    /// A single iteration of the benchmark.
+    pub probe: Probe,
+    /// The iteration of the benchmark over ranges of other lengths, for the sanity check.
+    pub rescale: Rescale,
+}
+
+impl Variant {
+    /// The full criterion id of the benchmark in `group`.
+    pub fn id(&self, group: &str) -> String {
+        match &self.parameter {
+            Some(parameter) => format!("{}/{}/{}", group, self.function, parameter),
+            None => format!("{}/{}", group, self.function),
+        }
+    }
+}
+
+thread_local! {
+    /// The benchmarks created so far, by their full criterion ids. The benchmarks are created and
+    /// run on the same thread.
+    static VARIANTS: RefCell<HashMap<String, Variant>> = RefCell::new(HashMap::new());
+}
+
+/// Registers the benchmarks of `group`, so their results can be exported.
+pub fn register(group: &str, variants: impl IntoIterator<Item = Variant>) {
+    VARIANTS.with(|registered| {
+        registered.borrow_mut().extend(
+            variants
+                .into_iter()
+                .map(|variant| (variant.id(group), variant)),
+        );
+    });
+}
+
+/// Returns the number of values yielded by `iter`, if it is known exactly and fits into `u64`.
+pub fn exact_len(iter: impl Iterator) -> Option<u64> {
+    match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => Some(lower as u64),
+        _ => None,
+    }
+}
+
+/// The exported results of a single benchmark. The times are in nanoseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// The full criterion id of the benchmark.
+    pub id: String,
+    /// The criterion group of the benchmark.
+    pub group: String,
+    /// The name of the range strategy.
+    pub strategy: String,
+    /// What is done with the range.
+    pub workload: String,
+    /// The integer type of the range.
+    pub integer: String,
+    /// The lower bound of the range.
+    pub low: String,
+    /// The upper bound of the range.
+    pub up: String,
+    /// The number of values consumed by a single iteration.
+    pub elements: Option<u64>,
+    /// The mean time of an iteration.
+    pub mean: f64,
+    /// The lower bound of the confidence interval of the mean.
+    pub mean_lower: f64,
+    /// The upper bound of the confidence interval of the mean.
+    pub mean_upper: f64,
+    /// The confidence level of the interval.
+    pub confidence_level: f64,
+    /// The median time of an iteration.
+    pub median: f64,
+    /// The standard deviation of the time of an iteration.
+    pub std_dev: f64,
+    /// The number of values consumed per second.
+    pub throughput: Option<f64>,
+    /// The instructions retired per iteration.
+    pub instructions: Option<f64>,
+    /// The CPU cycles per iteration.
+    pub cycles: Option<f64>,
+    /// The branch instructions retired per iteration.
+    pub branches: Option<f64>,
+    /// The mispredicted branches per iteration.
+    pub branch_misses: Option<f64>,
+}
+
+/// The results of a whole run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Export {
+    /// When the run started, in seconds since the Unix epoch.
+    pub started: u64,
+    /// The build of the benchmarks and the machine they ran on.
+    #[serde(default)]
+    pub environment: Option<Environment>,
+    /// The results of every benchmark measured during the run.
+    pub results: Vec<Record>,
+    /// The times of an iteration measured by every sample of each benchmark, by the benchmark ids.
+    /// They are not exported into the CSV file.
+    #[serde(default)]
+    pub samples: BTreeMap<String, Vec<f64>>,
+}
+
+impl Export {
+    /// Returns the samples of the benchmark of `record`, if they are known.
+    pub fn samples_of(&self, record: &Record) -> Option<&[f64]> {
+        self.samples.get(&record.id).map(Vec::as_slice)
+    }
+}
+
+/// The part of criterion's `benchmark.json` the export needs.
+#[derive(Deserialize)]
+struct BenchmarkId {
+    group_id: String,
+    full_id: String,
+}
+
+/// The part of criterion's `estimates.json` the export needs.
+#[derive(Deserialize)]
+struct Estimates {
+    #[serde(rename = "Mean")]
+    mean: Estimate,
+    #[serde(rename = "Median")]
+    median: Estimate,
+    #[serde(rename = "StdDev")]
+    std_dev: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    confidence_interval: ConfidenceInterval,
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct ConfidenceInterval {
+    confidence_level: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+/// The target directory, as criterion sees it: `CARGO_TARGET_DIR`, or `target` by default, which
+/// are relative to the current directory.
+pub fn target_dir() -> PathBuf {
+    let dir = env::var_os("CARGO_TARGET_DIR").map_or_else(|| "target".into(), PathBuf::from);
+    match env::current_dir() {
+        Ok(current) => current.join(dir),
+        Err(_) => dir,
+    }
+}
+
+/// The directory criterion saves its results into.
+pub fn criterion_dir() -> PathBuf {
+    target_dir().join("criterion")
+}
+
+/// The directory the exported results are written into.
+pub fn results_dir() -> PathBuf {
+    target_dir().join("range-perf")
+}
+
+/// Collects the paths of all the `new/benchmark.json` files under `dir`.
+fn find_benchmarks(dir: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_benchmarks(&path, found)?;
+        } else if path.ends_with("new/benchmark.json") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn load_json<T: for<'de> Deserialize<'de>>(path: &Path) -> io::Result<T> {
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
+
+/// Gathers the results of the registered benchmarks that criterion saved after `started`.
+pub fn collect(started: SystemTime) -> io::Result<Export> {
+    let variants = VARIANTS.with(|registered| registered.borrow().clone());
+    let mut benchmarks = Vec::new();
+    let criterion_dir = criterion_dir();
+    if criterion_dir.is_dir() {
+        find_benchmarks(&criterion_dir, &mut benchmarks)?;
+    }
+
+    let mut records = Vec::new();
+    let mut samples = BTreeMap::new();
+    for path in benchmarks {
+        if fs::metadata(&path)?.modified()? < started {
+            continue;
+        }
+        let id: BenchmarkId = load_json(&path)?;
+        let variant = match variants.get(&id.full_id) {
+            Some(variant) => variant,
+            None => continue,
+        };
+        let estimates: Estimates = load_json(&path.with_file_name("estimates.json"))?;
+        let (iterations, times): (Vec<f64>, Vec<f64>) =
+            load_json(&path.with_file_name("sample.json"))?;
+        samples.insert(
+            id.full_id.clone(),
+            times.iter().zip(&iterations).map(|(t, n)| t / n).collect(),
+        );
+        let mean = estimates.mean.point_estimate;
+        records.push(Record {
+            id: id.full_id,
+            group: id.group_id,
+            strategy: variant.strategy.to_owned(),
+            workload: variant.workload.clone(),
+            integer: variant.integer.to_owned(),
+            low: variant.low.clone(),
+            up: variant.up.clone(),
+            elements: variant.elements,
+            mean,
+            mean_lower: estimates.mean.confidence_interval.lower_bound,
+            mean_upper: estimates.mean.confidence_interval.upper_bound,
+            confidence_level: estimates.mean.confidence_interval.confidence_level,
+            median: estimates.median.point_estimate,
+            std_dev: estimates.std_dev.point_estimate,
+            throughput: variant
+                .elements
+                .map(|elements| elements as f64 / mean * 1e9),
+            instructions: None,
+            cycles: None,
+            branches: None,
+            branch_misses: None,
+        });
+    }
+    records.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(Export {
+        started: started
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+        environment: Some(Environment::capture()),
+        results: records,
+        samples,
+    })
+}
+
+/// Fills the hardware counters in, unless they are not available.
+pub fn count(records: &mut [Record]) {
+    let counters = match Counters::open() {
+        Ok(counters) => counters,
+        Err(error) => {
+            println!("The hardware counters are not available: {}", error);
+            return;
+        }
+    };
+    VARIANTS.with(|registered| {
+        let registered = registered.borrow();
+        for record in records {
+            let variant = match registered.get(&record.id) {
+                Some(variant) => variant,
+                None => continue,
+            };
+            match counters.measure(&variant.probe) {
+                Ok(counts) => {
+                    record.instructions = counts.instructions;
+                    record.cycles = counts.cycles;
+                    record.branches = counts.branches;
+                    record.branch_misses = counts.branch_misses;
+                }
+                Err(error) => eprintln!("Failed to count the events of {}: {}", record.id, error),
+            }
+        }
+    });
+}
+
+/// Creates the JSON file of a run that started at `started` in `dir`: `<started>.json`, or
+/// `<started>-<n>.json` with the first free `n` if the results of another run that started in the
+/// same second are already there.
+fn create_json(dir: &Path, started: u64) -> io::Result<(File, PathBuf)> {
+    let mut suffix = 0u32;
+    loop {
+        let name = match suffix {
+            0 => format!("{}.json", started),
+            n => format!("{}-{}.json", started, n),
+        };
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Parses the name of an exported JSON file into the time the run started and the suffix that
+/// tells apart the runs that started in the same second.
+fn parse_name(path: &Path) -> Option<(u64, u32)> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    match stem.split_once('-') {
+        Some((started, suffix)) => Some((started.parse().ok()?, suffix.parse().ok()?)),
+        None => Some((stem.parse().ok()?, 0)),
+    }
+}
+
+/// Writes `export` into `<dir>/<started>.json` and `<dir>/<started>.csv` (with a suffix if another
+/// run that started in the same second was exported there) and returns the path of the JSON file.
+pub fn write(export: &Export, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let (file, json) = create_json(dir, export.started)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), export)?;
+    let mut csv = csv::Writer::from_path(json.with_extension("csv"))?;
+    for record in &export.results {
+        csv.serialize(record)?;
+    }
+    csv.flush()?;
+    Ok(json)
+}
+
+/// Exports the results of the benchmarks measured since `started`, unless there are none.
+///
+/// Returns the exported results along with the path of the JSON file.
+pub fn run(started: SystemTime) -> io::Result<Option<(Export, PathBuf)>> {
+    let mut export = collect(started)?;
+    if export.results.is_empty() {
+        return Ok(None);
+    }
+    count(&mut export.results);
+    let path = write(&export, &results_dir())?;
+    Ok(Some((export, path)))
+}
+
+/// Loads the results exported into the JSON file at `path`.
+pub fn load(path: &Path) -> io::Result<Export> {
+    load_json(path)
+}
+
+/// Returns the path of the most recent JSON file in `dir`, if there is any.
+pub fn latest(dir: &Path) -> io::Result<Option<PathBuf>> {
+    let mut latest = None;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        // The files are named after the time the run started.
+        if let Some(started) = parse_name(&path) {
+            if latest.as_ref().is_none_or(|(time, _)| started > *time) {
+                latest = Some((started, path));
+            }
+        }
+    }
+    Ok(latest.map(|(_, path)| path))
+}
@@ -0,0 +1,393 @@
+//! Runs the benchmarks under a matrix of build profiles and toolchains and merges the results into
+//! one report.
+//!
+//! Every profile (a combination of the toolchain, optimization level, LTO, codegen units and target
+//! CPU) gets its own target directory, so switching between the profiles doesn't rebuild
+//! everything, and the criterion history of one profile is never compared with another.
+//!
+//! The toolchains are the ones installed with rustup; nothing is ever downloaded.
+
+use super::export::{self, Export};
+use super::summary::{self, format_time};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The settings of a release build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The rustup toolchain to build with, if not the current one.
+    pub toolchain: Option<String>,
+    /// The `opt-level` of the profile.
+    pub opt_level: String,
+    /// The `lto` of the profile.
+    pub lto: String,
+    /// The `codegen-units` of the profile.
+    pub codegen_units: u32,
+    /// The `-C target-cpu` to build for, if any.
+    pub target_cpu: Option<String>,
+}
+
+impl Profile {
+    /// A short name of the profile, which is also used as the name of its target directory.
+    pub fn name(&self) -> String {
+        let name = format!(
+            "opt-{}_lto-{}_cgu-{}_cpu-{}",
+            self.opt_level,
+            self.lto,
+            self.codegen_units,
+            self.target_cpu.as_deref().unwrap_or("default")
+        );
+        match &self.toolchain {
+            Some(toolchain) => format!("{}_{}", toolchain, name),
+            None => name,
+        }
+    }
+
+    /// The settings of the profile by name, including the `rustc -Vv` of the toolchain.
+    pub fn settings(&self) -> io::Result<BTreeMap<String, String>> {
+        let mut settings = BTreeMap::new();
+        settings.insert(
+            "toolchain".to_owned(),
+            self.toolchain
+                .clone()
+                .unwrap_or_else(|| "current".to_owned()),
+        );
+        settings.insert(
+            "rustc".to_owned(),
+            self.tool("rustc").arg("-Vv").output_text()?,
+        );
+        settings.insert("opt-level".to_owned(), self.opt_level.clone());
+        settings.insert("lto".to_owned(), self.lto.clone());
+        settings.insert("codegen-units".to_owned(), self.codegen_units.to_string());
+        settings.insert(
+            "target-cpu".to_owned(),
+            self.target_cpu
+                .clone()
+                .unwrap_or_else(|| "default".to_owned()),
+        );
+        Ok(settings)
+    }
+
+    /// A command that runs `tool` (`cargo` or `rustc`) of the toolchain of the profile.
+    fn tool(&self, tool: &str) -> Command {
+        match &self.toolchain {
+            Some(toolchain) => {
+                let mut command = Command::new("rustup");
+                command.args(["run", toolchain, tool]).env_remove("RUSTC");
+                command
+            }
+            None if tool == "cargo" => {
+                Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+            }
+            None => Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into())),
+        }
+    }
+
+    /// A `cargo` command that builds with this profile.
+    pub fn cargo(&self) -> Command {
+        let mut command = self.tool("cargo");
+        command
+            .env("CARGO_PROFILE_RELEASE_OPT_LEVEL", &self.opt_level)
+            .env("CARGO_PROFILE_RELEASE_LTO", &self.lto)
+            .env(
+                "CARGO_PROFILE_RELEASE_CODEGEN_UNITS",
+                self.codegen_units.to_string(),
+            );
+        if let Some(cpu) = &self.target_cpu {
+            let mut flags = env::var_os("RUSTFLAGS").unwrap_or_default();
+            if !flags.is_empty() {
+                flags.push(" ");
+            }
+            flags.push(format!("-C target-cpu={}", cpu));
+            command.env("RUSTFLAGS", flags);
+        }
+        command
+    }
+}
+
+/// Running a command for its output.
+trait OutputText {
+    /// Runs the command and returns its standard output, failing if the command fails.
+    fn output_text(&mut self) -> io::Result<String>;
+}
+
+impl OutputText for Command {
+    fn output_text(&mut self) -> io::Result<String> {
+        let output = self.output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "{:?} failed: {}",
+                self,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Returns the names of the toolchains installed with rustup.
+pub fn installed_toolchains() -> io::Result<Vec<String>> {
+    let list = Command::new("rustup")
+        .args(["toolchain", "list"])
+        .output_text()?;
+    // The lines look like `stable-x86_64-unknown-linux-gnu (active, default)`.
+    Ok(list
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| !name.is_empty() && *name != "no")
+        .map(str::to_owned)
+        .collect())
+}
+
+/// The values of every setting to combine into profiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    /// The toolchains, where `None` stands for the current one.
+    pub toolchains: Vec<Option<String>>,
+    /// The optimization levels.
+    pub opt_levels: Vec<String>,
+    /// The LTO settings.
+    pub ltos: Vec<String>,
+    /// The numbers of codegen units.
+    pub codegen_units: Vec<u32>,
+    /// The target CPUs, where `None` stands for the default one.
+    pub target_cpus: Vec<Option<String>>,
+}
+
+impl Default for Matrix {
+    /// Optimization levels 2 and 3 for the default and the native CPU, with the current toolchain.
+    fn default() -> Self {
+        Matrix {
+            toolchains: vec![None],
+            opt_levels: vec!["2".to_owned(), "3".to_owned()],
+            ltos: vec!["off".to_owned()],
+            codegen_units: vec![16],
+            target_cpus: vec![None, Some("native".to_owned())],
+        }
+    }
+}
+
+impl Matrix {
+    /// Every combination of the settings.
+    pub fn profiles(&self) -> Vec<Profile> {
+        let mut profiles = Vec::new();
+        for toolchain in &self.toolchains {
+            for opt_level in &self.opt_levels {
+                for lto in &self.ltos {
+                    for &codegen_units in &self.codegen_units {
+                        for target_cpu in &self.target_cpus {
+                            profiles.push(Profile {
+                                toolchain: toolchain.clone(),
+                                opt_level: opt_level.clone(),
+                                lto: lto.clone(),
+                                codegen_units,
+                                target_cpu: target_cpu.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        profiles
+    }
+}
+
+/// The results of the benchmarks under a single configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    /// The name of the configuration.
+    pub configuration: String,
+    /// The settings of the configuration by name.
+    pub settings: BTreeMap<String, String>,
+    /// The results.
+    pub export: Export,
+}
+
+/// A configuration the benchmarks couldn't be run under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Failure {
+    /// The name of the configuration.
+    pub configuration: String,
+    /// What went wrong.
+    pub error: String,
+}
+
+/// The results of the benchmarks under several configurations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    /// When the first run started, in seconds since the Unix epoch.
+    pub started: u64,
+    /// The runs in the order they were made.
+    pub runs: Vec<Run>,
+    /// The configurations that failed to build or run, in the order they were tried.
+    #[serde(default)]
+    pub failures: Vec<Failure>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Runs the `range-perf` binary with `args` in `target_dir` with the `cargo` command, and returns
+/// the results it exported.
+pub fn run_benchmarks(
+    mut cargo: Command,
+    target_dir: &Path,
+    args: &[OsString],
+) -> io::Result<Export> {
+    let started = now();
+    let status = cargo
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["run", "--release", "--bin", "range-perf", "--"])
+        .args(args)
+        .env("CARGO_TARGET_DIR", target_dir)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "the benchmarks failed: {}",
+            status
+        )));
+    }
+    let latest = export::latest(&target_dir.join("range-perf"))?
+        .map(|path| export::load(&path))
+        .transpose()?;
+    match latest {
+        Some(export) if export.started >= started => Ok(export),
+        _ => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no benchmark was run, check the filter",
+        )),
+    }
+}
+
+/// Writes `report` into `<dir>/<started>.json` and returns the path of the file.
+pub fn write(report: &Report, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", report.started));
+    serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), report)?;
+    Ok(path)
+}
+
+/// Prints how every strategy compares to the non-inclusive range under each configuration, and the
+/// time of every benchmark under each configuration.
+pub fn print(report: &Report) {
+    let width = 16;
+    for (index, run) in report.runs.iter().enumerate() {
+        let release = run
+            .settings
+            .get("rustc")
+            .and_then(|rustc| rustc.lines().next())
+            .unwrap_or("");
+        println!("[{}] {} ({})", index, run.configuration, release);
+    }
+    for failure in &report.failures {
+        println!("[-] {} failed: {}", failure.configuration, failure.error);
+    }
+    println!();
+
+    print!("{:<24}", "strategy");
+    for index in 0..report.runs.len() {
+        print!(" {:>width$}", format!("[{}]", index), width = width);
+    }
+    println!();
+    let ratios: Vec<Vec<(String, f64)>> = report
+        .runs
+        .iter()
+        .map(|run| summary::mean_ratios(&run.export))
+        .collect();
+    let mut strategies: Vec<&str> = Vec::new();
+    for (name, _) in ratios.iter().flatten() {
+        if !strategies.contains(&name.as_str()) {
+            strategies.push(name);
+        }
+    }
+    for name in strategies {
+        print!("{:<24}", name);
+        for run in &ratios {
+            match run.iter().find(|(known, _)| known == name) {
+                Some((_, ratio)) => print!(" {:>width$}", format!("{:.2}x", ratio), width = width),
+                None => print!(" {:>width$}", "-", width = width),
+            }
+        }
+        println!();
+    }
+    println!(
+        "The geometric means of the times relative to the {} range.\n",
+        summary::BASELINE
+    );
+
+    let mut ids: Vec<&str> = Vec::new();
+    for record in report.runs.iter().flat_map(|run| &run.export.results) {
+        if !ids.contains(&record.id.as_str()) {
+            ids.push(&record.id);
+        }
+    }
+    let id_width = ids.iter().map(|id| id.len()).max().unwrap_or(0);
+    print!("{:<id_width$}", "benchmark", id_width = id_width);
+    for index in 0..report.runs.len() {
+        print!(" {:>width$}", format!("[{}]", index), width = width);
+    }
+    println!();
+    for id in ids {
+        print!("{:<id_width$}", id, id_width = id_width);
+        for run in &report.runs {
+            match run.export.results.iter().find(|record| record.id == id) {
+                Some(record) => print!(" {:>width$}", format_time(record.mean), width = width),
+                None => print!(" {:>width$}", "-", width = width),
+            }
+        }
+        println!();
+    }
+}
+
+/// Runs the benchmarks selected by `args` under every profile of `matrix`, writes the merged report
+/// into `<root>/<started>.json` and prints it. Every profile gets its own target directory under
+/// `root`.
+///
+/// A profile that fails (most often because its toolchain is older than the `rust-version` of the
+/// crate) is recorded in the report, and the remaining profiles are run anyway.
+pub fn run(matrix: &Matrix, args: &[OsString], root: &Path) -> io::Result<PathBuf> {
+    let mut report = Report {
+        started: now(),
+        runs: Vec::new(),
+        failures: Vec::new(),
+    };
+    for profile in matrix.profiles() {
+        let configuration = profile.name();
+        println!("Running the benchmarks with {}", configuration);
+        let target_dir = root.join(&configuration);
+        let run = profile.settings().and_then(|settings| {
+            let export = run_benchmarks(profile.cargo(), &target_dir, args)?;
+            Ok((settings, export))
+        });
+        match run {
+            Ok((settings, export)) => report.runs.push(Run {
+                configuration,
+                settings,
+                export,
+            }),
+            Err(error) => {
+                eprintln!(
+                    "Failed to run the benchmarks with {}: {}",
+                    configuration, error
+                );
+                report.failures.push(Failure {
+                    configuration,
+                    error: error.to_string(),
+                });
+            }
+        }
+    }
+    let path = write(&report, root)?;
+    print(&report);
+    Ok(path)
+}
@@ -0,0 +1,212 @@
+//! Checks that the benchmarks actually measure iteration.
+//!
+//! LLVM is able to compute some of the workloads (most notably [`Sum`](super::workload::Sum)) in
+//! closed form, in which case the time doesn't depend on the length of the range at all and the
+//! benchmark says nothing about the cost of the loop. The sanity check runs every selected
+//! benchmark over ranges of a few lengths and warns about those whose time doesn't grow linearly
+//! with the length.
+
+use super::counters::Probe;
+use super::environment::Environment;
+use super::export::Variant;
+use crate::Integer;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// The lengths every variant is run at, as long as they fit into its integer type.
+const LENGTHS: [u64; 4] = [1_000, 10_000, 100_000, 1_000_000];
+
+/// The minimum time a single measurement takes.
+const MEASUREMENT_TIME: Duration = Duration::from_millis(10);
+
+/// The scaling exponent below which a variant is reported as not scaling with the length.
+const CONSTANT_EXPONENT: f64 = 0.2;
+
+/// The scaling exponent below which a variant is reported as scaling sub-linearly.
+const SUB_LINEAR_EXPONENT: f64 = 0.8;
+
+/// How the time of a variant depends on the length of the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    /// The time grows (roughly) linearly with the length.
+    Linear,
+    /// The time grows, but noticeably slower than the length.
+    SubLinear,
+    /// The time doesn't depend on the length.
+    Constant,
+}
+
+impl Scaling {
+    /// Classifies the exponent `k` of `time ~ length^k`.
+    pub fn from_exponent(exponent: f64) -> Self {
+        if exponent < CONSTANT_EXPONENT {
+            Scaling::Constant
+        } else if exponent < SUB_LINEAR_EXPONENT {
+            Scaling::SubLinear
+        } else {
+            Scaling::Linear
+        }
+    }
+}
+
+/// Builds a single iteration of a benchmark over a range of any length, which ends where the range
+/// of the benchmark ends if there is enough room below it, or starts where it starts otherwise.
+#[derive(Clone)]
+pub struct Rescale(Rc<dyn Fn(u64) -> Option<Probe>>);
+
+impl Rescale {
+    /// Rescales the benchmark of the range from `low` to `up`, a single iteration of which over
+    /// any range is returned by `probe`.
+    pub fn new<T: Integer>(low: T, up: T, probe: impl Fn(T, T) -> Probe + 'static) -> Self {
+        Rescale(Rc::new(move |len| {
+            let last = u128::from(len.checked_sub(1)?);
+            let below = T::MIN.distance(up);
+            if below >= last {
+                Some(probe(T::MIN.forward(below - last), up))
+            } else if low.distance(T::MAX) >= last {
+                Some(probe(low, low.forward(last)))
+            } else {
+                None
+            }
+        }))
+    }
+
+    /// A single iteration over a range of `len` values, unless there are not as many values in
+    /// the integer type.
+    pub fn probe(&self, len: u64) -> Option<Probe> {
+        (self.0)(len)
+    }
+}
+
+impl fmt::Debug for Rescale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Rescale")
+    }
+}
+
+/// The result of the sanity check of a single variant.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The criterion id of the benchmark.
+    pub variant: String,
+    /// The time of a single run in nanoseconds at each of the [`LENGTHS`] that fit into the
+    /// integer type of the benchmark.
+    pub times: Vec<f64>,
+    /// The exponent `k` of the least-squares fit of `time ~ length^k`.
+    pub exponent: f64,
+}
+
+impl Report {
+    /// How the time of the variant depends on the length of the range.
+    pub fn scaling(&self) -> Scaling {
+        Scaling::from_exponent(self.exponent)
+    }
+}
+
+/// Fits `y ~ x^k` in the log-log space with the least squares and returns `k`.
+fn exponent(lengths: &[u64], times: &[f64]) -> f64 {
+    let points: Vec<(f64, f64)> = lengths
+        .iter()
+        .zip(times)
+        .map(|(&x, &y)| ((x as f64).ln(), y.max(f64::MIN_POSITIVE).ln()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    covariance / variance
+}
+
+/// Returns the time of a single run of `probe` in nanoseconds.
+fn measure(probe: &Probe) -> f64 {
+    let mut iterations = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            probe.run();
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= MEASUREMENT_TIME {
+            return elapsed.as_nanos() as f64 / f64::from(iterations);
+        }
+        iterations *= 2;
+    }
+}
+
+/// Runs every one of the `variants`, which are given along with their ids, over the ranges of the
+/// [`LENGTHS`]. The variants whose integer type is too narrow for two of the lengths are skipped.
+pub fn check(variants: &[(String, &Variant)]) -> Vec<Report> {
+    variants
+        .iter()
+        .filter_map(|(id, variant)| {
+            let probes: Vec<Probe> = LENGTHS
+                .iter()
+                .map_while(|&len| variant.rescale.probe(len))
+                .collect();
+            if probes.len() < 2 {
+                return None;
+            }
+            let times: Vec<f64> = probes.iter().map(measure).collect();
+            Some(Report {
+                variant: id.clone(),
+                exponent: exponent(&LENGTHS[..times.len()], &times),
+                times,
+            })
+        })
+        .collect()
+}
+
+/// Runs the sanity check of the `variants` and prints the results along with the warnings.
+///
+/// Returns the number of variants that do not scale linearly.
+pub fn run(variants: &[(String, &Variant)]) -> usize {
+    Environment::capture().print();
+    let width = variants.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+    println!(
+        "{:<width$} {}  exponent",
+        "variant",
+        LENGTHS
+            .iter()
+            .map(|len| format!("{:>12}", len))
+            .collect::<String>(),
+        width = width
+    );
+    let mut suspicious = 0;
+    for report in check(variants) {
+        let times: String = (0..LENGTHS.len())
+            .map(|index| match report.times.get(index) {
+                Some(time) => format!("{:>10.1}ns", time),
+                None => format!("{:>12}", "-"),
+            })
+            .collect();
+        println!(
+            "{:<width$} {}  {:.2}",
+            report.variant,
+            times,
+            report.exponent,
+            width = width
+        );
+        match report.scaling() {
+            Scaling::Linear => {}
+            Scaling::SubLinear => {
+                suspicious += 1;
+                println!(
+                    "warning: {} scales sub-linearly with the length, the loop might be partly \
+                     optimized away",
+                    report.variant
+                );
+            }
+            Scaling::Constant => {
+                suspicious += 1;
+                println!(
+                    "warning: {} does not scale with the length, the measurement is likely a \
+                     closed-form optimization rather than loop cost",
+                    report.variant
+                );
+            }
+        }
+    }
+    suspicious
+}
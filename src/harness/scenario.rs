@@ -0,0 +1,475 @@
+//! Benchmark scenarios declared in a TOML file, which are benched instead of the built-in
+//! benchmarks, so a new range can be measured without touching the harness.
+//!
+//! ```toml
+//! [[scenario]]
+//! # The name of the benchmark group, `scenario <n>` by default.
+//! name = "u8 up to the top"
+//! type = "u8"
+//! # The bounds are numbers or relative to the type: min, min+N, max or max-N.
+//! low = "min"
+//! # A single upper bound or a list of them.
+//! high = ["max-1", "max"]
+//! # Every strategy available for the type by default.
+//! strategies = ["inclusive", "dynamic"]
+//! # sum, rev, collect, step N, or one of the u64 workloads. sum by default.
+//! workload = "sum"
+//! # fold, for, while-let or for-each, for the summing workloads only. fold by default.
+//! iteration = "for"
+//! ```
+//!
+//! The command line selection still applies to the scenarios. The files are only read with the
+//! `scenarios` feature, which needs a newer toolchain than the rest of the harness.
+
+use super::selection::Bound;
+use super::workload::Iteration;
+use crate::Integer;
+use std::path::Path;
+use std::str::FromStr;
+#[cfg(feature = "scenarios")]
+use {
+    super::selection,
+    super::strategy::{
+        self, RangeStrategy, SteppedStrategy, SteppedVisitor, Strategies, TypeVisitor, Visitor,
+    },
+    super::workload::{self, Workload},
+    std::{any::type_name, fmt, fs, ops::Range},
+    toml_edit::{Document, Item, Table, Value},
+};
+
+/// A range benchmarked with every selected strategy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scenario {
+    /// The name of the benchmark group.
+    pub name: String,
+    /// The name of the integer type.
+    pub integer: String,
+    /// The lower bound of the range.
+    pub low: Bound,
+    /// The upper bounds of the range, each of which is benched separately.
+    pub highs: Vec<Bound>,
+    /// The names of the strategies, or none for every strategy.
+    pub strategies: Vec<String>,
+    /// The name of the workload.
+    pub workload: String,
+    /// How the summing workloads iterate over the range.
+    pub iteration: Iteration,
+}
+
+impl Scenario {
+    /// The lower bound and the upper bounds in the integer type `T`, or an error if one of them
+    /// doesn't fit into the type or the range would be empty.
+    pub fn bounds<T: Integer + FromStr>(&self) -> Result<(T, Vec<T>), String> {
+        let low: T = self
+            .low
+            .resolve()
+            .map_err(|error| format!("low {}", error))?;
+        let highs = self
+            .highs
+            .iter()
+            .map(|high| {
+                let up: T = high.resolve().map_err(|error| format!("high {}", error))?;
+                if up < low {
+                    Err(format!("low {} is above high {}", self.low, high))
+                } else {
+                    Ok(up)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((low, highs))
+    }
+
+    /// Describes everything that makes the scenario impossible to bench.
+    #[cfg(feature = "scenarios")]
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let step = self.workload.starts_with("step ");
+        let strategies = match strategies_of(&self.integer, step) {
+            Some(strategies) => strategies,
+            None => {
+                problems.push(format!(
+                    "unknown type {:?}, expected one of {}",
+                    self.integer,
+                    selection::integers().join(", ")
+                ));
+                return problems;
+            }
+        };
+        let u64_workloads = u64_workloads();
+        if let Some(Ok(0)) | Some(Err(_)) =
+            self.workload.strip_prefix("step ").map(str::parse::<usize>)
+        {
+            problems.push(format!("invalid step in {:?}", self.workload));
+        }
+        let summing = step || self.workload == "sum" || self.workload == "rev";
+        if !summing
+            && self.workload != "collect"
+            && !u64_workloads.contains(&self.workload.as_str())
+        {
+            problems.push(format!(
+                "unknown workload {:?}, expected sum, rev, collect, step N or one of {}",
+                self.workload,
+                u64_workloads.join(", ")
+            ));
+        }
+        if u64_workloads.contains(&self.workload.as_str()) && self.integer != "u64" {
+            problems.push(format!("the {} workload only runs over u64", self.workload));
+        }
+        if !summing && self.iteration != Iteration::Fold {
+            problems.push(format!(
+                "the {} workload can't be iterated with {}, only the summing ones can",
+                self.workload,
+                self.iteration.name()
+            ));
+        }
+        for name in &self.strategies {
+            if !strategies.contains(&name.as_str()) {
+                problems.push(format!(
+                    "strategy {:?} is not available for {} with the {} workload, expected one of {}",
+                    name,
+                    self.integer,
+                    self.workload,
+                    strategies.join(", ")
+                ));
+            }
+        }
+        if let Err(error) = check_bounds(self) {
+            problems.push(error);
+        }
+        problems
+    }
+}
+
+/// The names of the strategies available for the integer type `integer`, if it is known: the
+/// stepped ones if `stepped`, or the ones that build whole ranges.
+#[cfg(feature = "scenarios")]
+fn strategies_of(integer: &str, stepped: bool) -> Option<Vec<&'static str>> {
+    struct Names<'a> {
+        integer: &'a str,
+        stepped: bool,
+        names: Option<Vec<&'static str>>,
+    }
+
+    struct StrategyNames(Vec<&'static str>);
+
+    impl<T> Visitor<T> for StrategyNames {
+        fn visit<S: RangeStrategy<T>>(&mut self) {
+            self.0.push(S::NAME);
+        }
+    }
+
+    impl<T> SteppedVisitor<T> for StrategyNames {
+        fn visit<S: SteppedStrategy<T>>(&mut self) {
+            self.0.push(S::NAME);
+        }
+    }
+
+    impl TypeVisitor for Names<'_> {
+        fn visit<T: Strategies + FromStr>(&mut self) {
+            if type_name::<T>() == self.integer {
+                let mut strategies = StrategyNames(Vec::new());
+                if self.stepped {
+                    T::visit_stepped(&mut strategies);
+                } else {
+                    T::visit(&mut strategies);
+                }
+                self.names = Some(strategies.0);
+            }
+        }
+    }
+
+    let mut names = Names {
+        integer,
+        stepped,
+        names: None,
+    };
+    strategy::visit_types(&mut names);
+    names.names
+}
+
+/// Resolves the bounds of `scenario` in its integer type.
+#[cfg(feature = "scenarios")]
+fn check_bounds(scenario: &Scenario) -> Result<(), String> {
+    struct Check<'a> {
+        scenario: &'a Scenario,
+        result: Result<(), String>,
+    }
+
+    impl TypeVisitor for Check<'_> {
+        fn visit<T: Strategies + FromStr>(&mut self) {
+            if type_name::<T>() == self.scenario.integer {
+                self.result = self.scenario.bounds::<T>().map(|_| ());
+            }
+        }
+    }
+
+    let mut check = Check {
+        scenario,
+        result: Ok(()),
+    };
+    strategy::visit_types(&mut check);
+    check.result
+}
+
+/// The names of the workloads that only run over `u64`.
+#[cfg(feature = "scenarios")]
+fn u64_workloads() -> Vec<&'static str> {
+    struct Names(Vec<&'static str>);
+
+    impl workload::Visitor for Names {
+        fn visit<W: Workload>(&mut self, workload: W) {
+            if workload.name() != "sum" {
+                self.0.push(workload.name());
+            }
+        }
+    }
+
+    let mut names = Names(Vec::new());
+    workload::visit(&mut names);
+    names.0
+}
+
+/// A problem with a scenario file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "scenarios")]
+pub struct Problem {
+    /// The line the problem was found at, starting from 1.
+    pub line: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+#[cfg(feature = "scenarios")]
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Collects the problems of a scenario file along with their lines.
+#[cfg(feature = "scenarios")]
+struct Problems<'a> {
+    text: &'a str,
+    problems: Vec<Problem>,
+}
+
+#[cfg(feature = "scenarios")]
+impl Problems<'_> {
+    /// Records a problem found at `span`, or at the start of the file if there is no span.
+    fn push(&mut self, span: Option<Range<usize>>, message: String) {
+        let line = span.map_or(1, |span| self.text[..span.start].matches('\n').count() + 1);
+        self.problems.push(Problem { line, message });
+    }
+
+    /// Reads a bound, which is either a number or a string.
+    fn bound(&mut self, key: &str, value: &Value) -> Option<Bound> {
+        let bound = match (value.as_integer(), value.as_str()) {
+            (Some(number), _) => number.to_string().parse(),
+            (_, Some(text)) => text.parse(),
+            _ => Err(format!("{} must be a number or a string", key)),
+        };
+        bound
+            .map_err(|error| self.push(value.span(), format!("{}: {}", key, error)))
+            .ok()
+    }
+
+    /// Reads a string.
+    fn string(&mut self, key: &str, item: &Item) -> Option<String> {
+        match item.as_str() {
+            Some(text) => Some(text.to_owned()),
+            None => {
+                self.push(item.span(), format!("{} must be a string", key));
+                None
+            }
+        }
+    }
+
+    /// Reads a single scenario, which is the `index`-th one of the file.
+    fn scenario(&mut self, index: usize, table: &Table) -> Option<Scenario> {
+        let count = self.problems.len();
+        let mut scenario = Scenario {
+            name: format!("scenario {}", index + 1),
+            integer: String::new(),
+            low: Bound::Min(0),
+            highs: Vec::new(),
+            strategies: Vec::new(),
+            workload: "sum".to_owned(),
+            iteration: Iteration::Fold,
+        };
+        let (mut integer, mut low, mut high) = (false, false, false);
+        // The unknown keys don't get in the way of checking the rest of the scenario.
+        let mut unknown = Vec::new();
+        for (key, item) in table.iter() {
+            match key {
+                "name" => scenario.name = self.string(key, item).unwrap_or_default(),
+                "type" => {
+                    integer = true;
+                    scenario.integer = self.string(key, item).unwrap_or_default();
+                }
+                "low" => {
+                    low = true;
+                    match item.as_value() {
+                        Some(value) => {
+                            if let Some(bound) = self.bound(key, value) {
+                                scenario.low = bound;
+                            }
+                        }
+                        None => self.push(item.span(), "low must be a single bound".to_owned()),
+                    }
+                }
+                "high" => {
+                    high = true;
+                    let values: Vec<&Value> = match item.as_value() {
+                        Some(Value::Array(array)) => array.iter().collect(),
+                        Some(value) => vec![value],
+                        None => Vec::new(),
+                    };
+                    if values.is_empty() {
+                        self.push(
+                            item.span(),
+                            "high must be a bound or a non-empty list of bounds".to_owned(),
+                        );
+                    }
+                    for value in values {
+                        if let Some(bound) = self.bound(key, value) {
+                            scenario.highs.push(bound);
+                        }
+                    }
+                }
+                "strategies" => match item.as_array() {
+                    Some(array) => {
+                        for value in array.iter() {
+                            match value.as_str() {
+                                Some(name) => scenario.strategies.push(name.to_owned()),
+                                None => {
+                                    self.push(value.span(), "strategies must be strings".to_owned())
+                                }
+                            }
+                        }
+                    }
+                    None => self.push(
+                        item.span(),
+                        "strategies must be a list of strings".to_owned(),
+                    ),
+                },
+                "workload" => {
+                    if let Some(workload) = self.string(key, item) {
+                        scenario.workload = workload;
+                    }
+                }
+                "iteration" => {
+                    if let Some(name) = self.string(key, item) {
+                        match Iteration::ALL.iter().find(|style| style.name() == name) {
+                            Some(&iteration) => scenario.iteration = iteration,
+                            None => self.push(
+                                item.span(),
+                                format!(
+                                    "unknown iteration {:?}, expected fold, for, while-let or \
+                                     for-each",
+                                    name
+                                ),
+                            ),
+                        }
+                    }
+                }
+                _ => unknown.push((item.span(), format!("unknown key {:?}", key))),
+            }
+        }
+        for (present, key) in [(integer, "type"), (low, "low"), (high, "high")] {
+            if !present {
+                self.push(table.span(), format!("{} is missing", key));
+            }
+        }
+        if self.problems.len() == count {
+            for problem in scenario.problems() {
+                self.push(table.span(), problem);
+            }
+        }
+        for (span, problem) in unknown {
+            self.push(span, problem);
+        }
+        if self.problems.len() > count {
+            None
+        } else {
+            Some(scenario)
+        }
+    }
+}
+
+/// Parses the `[[scenario]]` tables of a TOML document and checks that every scenario can be
+/// benched.
+#[cfg(feature = "scenarios")]
+pub fn parse(text: &str) -> Result<Vec<Scenario>, Vec<Problem>> {
+    let mut problems = Problems {
+        text,
+        problems: Vec::new(),
+    };
+    let document = match Document::parse(text) {
+        Ok(document) => document,
+        Err(error) => {
+            problems.push(error.span(), error.message().to_owned());
+            return Err(problems.problems);
+        }
+    };
+    let root = document.as_table();
+    for (key, item) in root.iter() {
+        if key != "scenario" {
+            problems.push(item.span(), format!("unknown key {:?}", key));
+        }
+    }
+    let tables = match root.get("scenario").map(Item::as_array_of_tables) {
+        Some(Some(tables)) => tables,
+        Some(None) => {
+            let span = root.get("scenario").and_then(Item::span);
+            problems.push(
+                span,
+                "scenario must be an array of [[scenario]] tables".to_owned(),
+            );
+            return Err(problems.problems);
+        }
+        None => {
+            problems.push(None, "there are no [[scenario]] tables".to_owned());
+            return Err(problems.problems);
+        }
+    };
+    let mut scenarios: Vec<Scenario> = Vec::new();
+    for (index, table) in tables.iter().enumerate() {
+        if let Some(scenario) = problems.scenario(index, table) {
+            if scenarios.iter().any(|known| known.name == scenario.name) {
+                problems.push(
+                    table.span(),
+                    format!("there is another scenario named {:?}", scenario.name),
+                );
+            }
+            scenarios.push(scenario);
+        }
+    }
+    if problems.problems.is_empty() {
+        Ok(scenarios)
+    } else {
+        Err(problems.problems)
+    }
+}
+
+/// Loads the scenarios from the file at `path`. The error lists every problem with the file.
+#[cfg(feature = "scenarios")]
+pub fn load(path: &Path) -> Result<Vec<Scenario>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    parse(&text).map_err(|problems| {
+        problems
+            .iter()
+            .map(|problem| format!("{}:{}: {}", path.display(), problem.line, problem.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Fails to load the scenarios, since reading them needs the `scenarios` feature.
+#[cfg(not(feature = "scenarios"))]
+pub fn load(path: &Path) -> Result<Vec<Scenario>, String> {
+    Err(format!(
+        "{}: the scenario files can only be read with the scenarios feature",
+        path.display()
+    ))
+}
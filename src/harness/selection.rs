@@ -0,0 +1,450 @@
+//! The command line of the benchmarks: which strategies, integer types, bounds and workloads to
+//! bench, how long to measure them for, and what to do with them.
+//!
+//! ```text
+//! range-perf [--strategy <name>,...] [--type <integer>,...] [--bound <bound>,...]
+//!            [--workload <name>,...] [--sample-size <n>] [--measurement-time <seconds>]
+//!            [--save-baseline <name> | --baseline <name>] [--noplot]
+//!            [--scenario <file>] [--list | --sanity] [<filter>]
+//! range-perf --summary [<results.json>] | --pairwise [<results.json>]
+//!            | --compare <baseline.json> [<results.json>] | --codegen
+//! ```
+
+use super::strategy::{self, Strategies, TypeVisitor};
+use super::workload::{self, Visitor, Workload};
+use crate::Integer;
+use criterion::Criterion;
+use std::any::type_name;
+use std::fmt;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The names of the integer types the ranges can be built over.
+pub fn integers() -> Vec<&'static str> {
+    struct Names(Vec<&'static str>);
+
+    impl TypeVisitor for Names {
+        fn visit<T: Strategies + FromStr>(&mut self) {
+            self.0.push(type_name::<T>());
+        }
+    }
+
+    let mut names = Names(Vec::new());
+    strategy::visit_types(&mut names);
+    names.0
+}
+
+/// The names of every strategy, including the ones that only step through the ranges.
+pub fn strategies() -> Vec<&'static str> {
+    strategy::names()
+}
+
+/// The names of every workload: the ways the ranges of every integer type are consumed, the
+/// workloads over the `u64` ranges and the iteration styles.
+pub fn workloads() -> Vec<&'static str> {
+    struct Names(Vec<&'static str>);
+
+    impl Visitor for Names {
+        fn visit<W: Workload>(&mut self, workload: W) {
+            if !self.0.contains(&workload.name()) {
+                self.0.push(workload.name());
+            }
+        }
+    }
+
+    let mut names = Names(vec!["sum", "rev", "collect", "step"]);
+    workload::visit(&mut names);
+    names.visit(workload::ForLoop);
+    names.visit(workload::WhileLet);
+    names.visit(workload::ForEach);
+    names.0
+}
+
+/// A bound of a range, either relative to the boundaries of the integer type or absolute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bound {
+    /// `min` or `min+N`: the smallest value of the type plus `N`.
+    Min(u128),
+    /// `max` or `max-N`: the largest value of the type minus `N`.
+    Max(u128),
+    /// A number, which only applies to the types it fits into.
+    Value(String),
+}
+
+impl Bound {
+    /// The value of the bound in the type `T`, or an error if it doesn't fit into the type.
+    pub fn resolve<T: Integer + FromStr>(&self) -> Result<T, String> {
+        let span = T::MIN.distance(T::MAX);
+        let value = match self {
+            Bound::Min(n) if *n <= span => Some(T::MIN.forward(*n)),
+            Bound::Max(n) if *n <= span => Some(T::MIN.forward(span - n)),
+            Bound::Value(value) => value.parse().ok(),
+            _ => None,
+        };
+        value.ok_or_else(|| format!("{} is out of the range of {}", self, type_name::<T>()))
+    }
+}
+
+impl FromStr for Bound {
+    type Err = String;
+
+    fn from_str(bound: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid bound {:?}: expected min, min+N, max, max-N or a number",
+                bound
+            )
+        };
+        let offset = |n: &str| n.parse::<u128>().map_err(|_| invalid());
+        match bound {
+            "min" => Ok(Bound::Min(0)),
+            "max" => Ok(Bound::Max(0)),
+            _ if bound.starts_with("min+") => offset(&bound[4..]).map(Bound::Min),
+            _ if bound.starts_with("max-") => offset(&bound[4..]).map(Bound::Max),
+            _ if bound.parse::<u128>().is_ok() || bound.parse::<i128>().is_ok() => {
+                Ok(Bound::Value(bound.to_owned()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bound::Min(0) => f.write_str("min"),
+            Bound::Min(n) => write!(f, "min+{}", n),
+            Bound::Max(0) => f.write_str("max"),
+            Bound::Max(n) => write!(f, "max-{}", n),
+            Bound::Value(value) => f.write_str(value),
+        }
+    }
+}
+
+/// The benchmarks to create. An empty list selects the default ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// The names of the strategies.
+    pub strategies: Vec<String>,
+    /// The names of the integer types.
+    pub integers: Vec<String>,
+    /// The upper bounds of the ranges.
+    pub bounds: Vec<Bound>,
+    /// The names of the workloads.
+    pub workloads: Vec<String>,
+}
+
+impl Selection {
+    /// Whether the strategy `name` is selected.
+    pub fn strategy(&self, name: &str) -> bool {
+        self.strategies.is_empty() || self.strategies.iter().any(|known| known == name)
+    }
+
+    /// Whether the integer type `T` is selected, where `by_default` tells whether it is benched
+    /// when no type is selected explicitly.
+    pub fn integer<T: Integer>(&self, by_default: bool) -> bool {
+        if self.integers.is_empty() {
+            by_default
+        } else {
+            self.integers.iter().any(|known| known == type_name::<T>())
+        }
+    }
+
+    /// Whether the workload `name` is selected. Only the first word of the name counts, so `step`
+    /// selects the workloads `step 1`, `step 3` and so on.
+    pub fn workload(&self, name: &str) -> bool {
+        let kind = name.split(' ').next().unwrap_or(name);
+        self.workloads.is_empty() || self.workloads.iter().any(|known| known == kind)
+    }
+
+    /// The upper bounds of the ranges of `T` that start at `low`: the selected bounds that fit
+    /// into `T` and are not below `low`, or `defaults` if no bound is selected.
+    pub fn bounds<T: Integer + FromStr>(&self, low: T, defaults: Vec<T>) -> Vec<T> {
+        if self.bounds.is_empty() {
+            return defaults;
+        }
+        self.bounds
+            .iter()
+            .filter_map(|bound| bound.resolve().ok())
+            .filter(|&up| up >= low)
+            .collect()
+    }
+
+    /// The selection further restricted to `strategies`, unless there are none, or nothing if none
+    /// of them is selected.
+    pub fn narrow(&self, strategies: &[String]) -> Option<Selection> {
+        if strategies.is_empty() {
+            return Some(self.clone());
+        }
+        let strategies: Vec<String> = strategies
+            .iter()
+            .filter(|name| self.strategy(name))
+            .cloned()
+            .collect();
+        if strategies.is_empty() {
+            None
+        } else {
+            Some(Selection {
+                strategies,
+                ..self.clone()
+            })
+        }
+    }
+
+    /// Whether a range with the fixed upper bound `up` is selected.
+    pub fn up<T: Integer + FromStr>(&self, up: T) -> bool {
+        self.bounds.is_empty()
+            || self
+                .bounds
+                .iter()
+                .any(|bound| bound.resolve::<T>() == Ok(up))
+    }
+}
+
+/// A named criterion baseline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Baseline {
+    /// Saves the results as the baseline, comparing them with the previous ones saved under it.
+    Save(String),
+    /// Compares the results with the baseline without overwriting it.
+    Compare(String),
+}
+
+/// What the program does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Runs the selected benchmarks and exports their results.
+    #[default]
+    Bench,
+    /// Lists the selected benchmarks instead of running them.
+    List,
+    /// Checks that the selected benchmarks measure iteration.
+    Sanity,
+    /// Dumps the code of every strategy.
+    Codegen,
+    /// Prints the summary of the saved run, or of the latest one if there is no path.
+    Summary(Option<PathBuf>),
+    /// Compares every pair of strategies of the saved run, or of the latest one if there is no
+    /// path.
+    Pairwise(Option<PathBuf>),
+    /// Compares the saved run (or the latest one if there is no path) to the `baseline` run.
+    Compare {
+        baseline: PathBuf,
+        current: Option<PathBuf>,
+    },
+}
+
+/// The options of a benchmark run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    /// The benchmarks to create.
+    pub selection: Selection,
+    /// Skips the benchmarks whose ids don't contain the filter.
+    pub filter: Option<String>,
+    /// The number of samples criterion takes of every benchmark.
+    pub sample_size: Option<usize>,
+    /// The time criterion spends measuring every benchmark.
+    pub measurement_time: Option<Duration>,
+    /// The criterion baseline, `base` (which is saved) by default.
+    pub baseline: Option<Baseline>,
+    /// Disables the plots and the HTML report of criterion.
+    pub noplot: bool,
+    /// What is done with the selected benchmarks, or instead of them.
+    pub mode: Mode,
+    /// The scenario files whose benchmarks replace the built-in ones.
+    pub scenarios: Vec<PathBuf>,
+}
+
+/// Splits a comma-separated list of names and checks that every one of them is `known`.
+fn names(what: &str, values: &str, known: &[&str]) -> Result<Vec<String>, String> {
+    values
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            if known.contains(&name) {
+                Ok(name.to_owned())
+            } else {
+                Err(format!(
+                    "unknown {} {:?}, expected one of {}",
+                    what,
+                    name,
+                    known.join(", ")
+                ))
+            }
+        })
+        .collect()
+}
+
+impl Options {
+    /// Parses the command line arguments (without the name of the program).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let flag = arg.as_str();
+            let mode = match flag {
+                "--list" => Some(Mode::List),
+                "--sanity" => Some(Mode::Sanity),
+                "--codegen" => Some(Mode::Codegen),
+                "--summary" | "--pairwise" => {
+                    let path = args.next_if(|arg| !arg.starts_with('-')).map(PathBuf::from);
+                    match flag {
+                        "--summary" => Some(Mode::Summary(path)),
+                        _ => Some(Mode::Pairwise(path)),
+                    }
+                }
+                "--compare" => match args.next_if(|arg| !arg.starts_with('-')) {
+                    Some(baseline) => Some(Mode::Compare {
+                        baseline: baseline.into(),
+                        current: args.next_if(|arg| !arg.starts_with('-')).map(PathBuf::from),
+                    }),
+                    None => return Err("--compare needs the results of the baseline run".into()),
+                },
+                _ => None,
+            };
+            if let Some(mode) = mode {
+                if options.mode != Mode::Bench {
+                    return Err(format!("{} can't be combined with another mode", flag));
+                }
+                options.mode = mode;
+                continue;
+            }
+            match flag {
+                // Passed by `cargo bench`.
+                "--bench" => continue,
+                "--noplot" | "-n" => {
+                    options.noplot = true;
+                    continue;
+                }
+                _ if !flag.starts_with('-') => {
+                    if options.filter.replace(arg.clone()).is_some() {
+                        return Err(format!("unexpected argument {:?}", arg));
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} needs a value", flag))?;
+            let selection = &mut options.selection;
+            match flag {
+                "--strategy" => {
+                    let strategies = names("strategy", &value, &strategies())?;
+                    selection.strategies.extend(strategies)
+                }
+                "--type" => {
+                    let integers = names("integer type", &value, &integers())?;
+                    selection.integers.extend(integers)
+                }
+                "--workload" => {
+                    let workloads = names("workload", &value, &workloads())?;
+                    selection.workloads.extend(workloads)
+                }
+                "--bound" => {
+                    for bound in value.split(',') {
+                        selection.bounds.push(bound.trim().parse()?);
+                    }
+                }
+                "--save-baseline" | "-s" | "--baseline" | "-b" => {
+                    let baseline = match flag {
+                        "--save-baseline" | "-s" => Baseline::Save(value),
+                        _ => Baseline::Compare(value),
+                    };
+                    if options.baseline.replace(baseline).is_some() {
+                        return Err("only one baseline can be saved or compared with".to_owned());
+                    }
+                }
+                "--scenario" => options.scenarios.push(value.into()),
+                "--sample-size" => match value.parse() {
+                    Ok(size) if size >= 2 => options.sample_size = Some(size),
+                    _ => return Err(format!("invalid sample size {:?}", value)),
+                },
+                "--measurement-time" => match value.parse::<f64>() {
+                    Ok(seconds) if seconds > 0. && seconds.is_finite() => {
+                        options.measurement_time = Some(Duration::from_secs_f64(seconds))
+                    }
+                    _ => return Err(format!("invalid measurement time {:?}", value)),
+                },
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Parses the command line arguments, and exits with the usage if they are invalid or the help
+    /// is asked for.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let args: Vec<String> = args.into_iter().collect();
+        if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+            println!("{}", usage());
+            process::exit(0);
+        }
+        Options::parse(args).unwrap_or_else(|error| {
+            eprintln!("error: {}\n\n{}", error, usage());
+            process::exit(2);
+        })
+    }
+
+    /// A criterion runner configured with the options.
+    pub fn criterion(&self) -> Criterion {
+        let mut criterion = Criterion::default();
+        if let Some(size) = self.sample_size {
+            criterion = criterion.sample_size(size);
+        }
+        if let Some(time) = self.measurement_time {
+            criterion = criterion.measurement_time(time);
+        }
+        if let Some(filter) = &self.filter {
+            criterion = criterion.with_filter(filter.clone());
+        }
+        criterion = match &self.baseline {
+            Some(Baseline::Save(name)) => criterion.save_baseline(name.clone()),
+            Some(Baseline::Compare(name)) => criterion.retain_baseline(name.clone()),
+            None => criterion,
+        };
+        if self.noplot {
+            criterion.without_plots()
+        } else {
+            criterion.with_plots()
+        }
+    }
+}
+
+/// The usage of the benchmarks along with the names every option takes.
+pub fn usage() -> String {
+    format!(
+        "Usage: range-perf [options] [<filter>]
+
+Runs the benchmarks whose ids contain the filter, or all of them.
+
+Options:
+    --strategy <name>,...       {}
+    --type <integer>,...        {}
+    --bound <bound>,...         the upper bounds: min, min+N, max, max-N or a number
+    --workload <name>,...       {}
+    --sample-size <n>           the number of samples of every benchmark
+    --measurement-time <secs>   the time to measure every benchmark for
+    -s, --save-baseline <name>  saves the results as a criterion baseline (base by default)
+    -b, --baseline <name>       compares the results with a criterion baseline
+    -n, --noplot                disables the plots and the HTML report of criterion
+    --scenario <file>           benches the scenarios of a TOML file instead of the built-in ones
+    --list                      lists the selected benchmarks instead of running them
+    --sanity                    checks that the selected benchmarks measure iteration
+
+Instead of the benchmarks:
+    --summary [<results.json>]                 prints the summary of a saved run
+    --compare <baseline.json> [<results.json>] compares a saved run to a baseline run
+    --pairwise [<results.json>]                compares every pair of strategies of a saved run
+    --codegen                                  dumps the code of every strategy
+
+By default, the ranges of u8, u16, u32, u64, usize, i32, i64 and u128 are benched up to max-1 and
+max. The selected bounds replace those, and select the u64 workloads over the ranges that end at
+one of them.",
+        strategies().join(", "),
+        integers().join(", "),
+        workloads().join(", ")
+    )
+}
@@ -0,0 +1,226 @@
+//! Statistical tests of whether two sets of samples (such as the iteration times of two
+//! strategies) differ.
+
+use std::f64::consts::PI;
+
+/// The p-value below which a difference is considered significant.
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Returns the mean and the unbiased variance of `samples`.
+fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.);
+    (mean, variance)
+}
+
+/// The result of Welch's t-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Welch {
+    /// The t statistic, positive when the first sample set has the greater mean.
+    pub t: f64,
+    /// The Welch–Satterthwaite degrees of freedom.
+    pub df: f64,
+    /// The two-sided p-value.
+    pub p: f64,
+}
+
+/// Runs Welch's t-test, which doesn't assume the variances of `a` and `b` to be equal.
+///
+/// Both sets need at least two samples.
+pub fn welch(a: &[f64], b: &[f64]) -> Welch {
+    assert!(a.len() > 1 && b.len() > 1, "Not enough samples");
+    let (mean_a, var_a) = mean_and_variance(a);
+    let (mean_b, var_b) = mean_and_variance(b);
+    let (se_a, se_b) = (var_a / a.len() as f64, var_b / b.len() as f64);
+    let se = se_a + se_b;
+    if se == 0. {
+        // Both sets are constant, so they either are the same or differ for sure.
+        let df = (a.len() + b.len() - 2) as f64;
+        return if mean_a == mean_b {
+            Welch { t: 0., df, p: 1. }
+        } else {
+            Welch {
+                t: (mean_a - mean_b).signum() * f64::INFINITY,
+                df,
+                p: 0.,
+            }
+        };
+    }
+    let t = (mean_a - mean_b) / se.sqrt();
+    let df =
+        se.powi(2) / (se_a.powi(2) / (a.len() - 1) as f64 + se_b.powi(2) / (b.len() - 1) as f64);
+    Welch {
+        t,
+        df,
+        p: student_t_two_sided(t, df),
+    }
+}
+
+/// Returns Cohen's d of `a` against `b`: the difference of the means in the units of the pooled
+/// standard deviation.
+pub fn cohens_d(a: &[f64], b: &[f64]) -> f64 {
+    let (mean_a, var_a) = mean_and_variance(a);
+    let (mean_b, var_b) = mean_and_variance(b);
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+    let pooled = ((n_a - 1.) * var_a + (n_b - 1.) * var_b) / (n_a + n_b - 2.);
+    if pooled == 0. {
+        return if mean_a == mean_b {
+            0.
+        } else {
+            (mean_a - mean_b).signum() * f64::INFINITY
+        };
+    }
+    (mean_a - mean_b) / pooled.sqrt()
+}
+
+/// The conventional magnitude of an effect size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// `|d| < 0.2`.
+    Negligible,
+    /// `0.2 <= |d| < 0.5`.
+    Small,
+    /// `0.5 <= |d| < 0.8`.
+    Medium,
+    /// `|d| >= 0.8`.
+    Large,
+}
+
+impl Effect {
+    /// Classifies Cohen's d.
+    pub fn from_d(d: f64) -> Self {
+        match d.abs() {
+            d if d < 0.2 => Effect::Negligible,
+            d if d < 0.5 => Effect::Small,
+            d if d < 0.8 => Effect::Medium,
+            _ => Effect::Large,
+        }
+    }
+
+    /// The name of the magnitude.
+    pub fn name(self) -> &'static str {
+        match self {
+            Effect::Negligible => "negligible",
+            Effect::Small => "small",
+            Effect::Medium => "medium",
+            Effect::Large => "large",
+        }
+    }
+}
+
+/// The comparison of two sample sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// The ratio of the mean of the first set to the mean of the second one.
+    pub ratio: f64,
+    /// The result of Welch's t-test.
+    pub welch: Welch,
+    /// Cohen's d of the first set against the second one.
+    pub d: f64,
+}
+
+impl Comparison {
+    /// Compares `a` against `b`.
+    pub fn new(a: &[f64], b: &[f64]) -> Self {
+        let mean = |samples: &[f64]| samples.iter().sum::<f64>() / samples.len() as f64;
+        Comparison {
+            ratio: mean(a) / mean(b),
+            welch: welch(a, b),
+            d: cohens_d(a, b),
+        }
+    }
+
+    /// Whether the difference is significant at [`SIGNIFICANCE_LEVEL`].
+    pub fn significant(&self) -> bool {
+        self.welch.p < SIGNIFICANCE_LEVEL
+    }
+
+    /// The magnitude of the difference.
+    pub fn effect(&self) -> Effect {
+        Effect::from_d(self.d)
+    }
+}
+
+/// Returns the probability that the absolute value of a variable with Student's t-distribution
+/// with `df` degrees of freedom exceeds `|t|`.
+pub fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    if t.is_infinite() {
+        return 0.;
+    }
+    incomplete_beta(df / (df + t * t), df / 2., 0.5)
+}
+
+/// The natural logarithm of the gamma function, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // The reflection formula.
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1. - x);
+    }
+    let x = x - 1.;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.));
+    0.5 * (2. * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// The regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    if x >= 1. {
+        return 1.;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1. - x).ln()).exp();
+    // The continued fraction converges quickly only below this point, above it the symmetry
+    // `I_x(a, b) = 1 - I_{1-x}(b, a)` is used.
+    if x < (a + 1.) / (a + b + 2.) {
+        front * beta_fraction(x, a, b) / a
+    } else {
+        1. - front * beta_fraction(1. - x, b, a) / b
+    }
+}
+
+/// The continued fraction of the incomplete beta function, by the modified Lentz's method.
+fn beta_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-15;
+    const MAX_ITERATIONS: u32 = 1_000;
+
+    let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.;
+    let mut d = 1. / clamp(1. - (a + b) * x / (a + 1.));
+    let mut fraction = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = f64::from(m);
+        // The even and the odd steps of the fraction.
+        let even = m * (b - m) * x / ((a + 2. * m - 1.) * (a + 2. * m));
+        d = 1. / clamp(1. + even * d);
+        c = clamp(1. + even / c);
+        fraction *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2. * m) * (a + 2. * m + 1.));
+        d = 1. / clamp(1. + odd * d);
+        c = clamp(1. + odd / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction
+}
@@ -0,0 +1,303 @@
+//! The range strategies under test and the registry of the ones available for every integer type.
+
+use crate::{
+    DoWhileRange, DynamicInclusiveRange, DynamicSteppedRange, FlaggedRange, Integer, Narrow,
+    OptionEndRange, WideRange,
+};
+use std::iter;
+use std::marker::PhantomData;
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+
+/// A way of iterating over the values between two bounds.
+pub trait RangeStrategy<T>: 'static {
+    /// A short name of the strategy to be used in benchmark ids.
+    const NAME: &'static str;
+
+    /// Whether LLVM is known to sum the ranges of the strategy in closed form, without which the
+    /// full ranges of the wide integer types take ages to go through.
+    const CLOSED_FORM: bool = true;
+
+    /// The iterator the strategy builds.
+    type Iter: DoubleEndedIterator<Item = T>;
+
+    /// Builds an iterator over the values from `low` to `up`, both ends included (unless stated
+    /// otherwise by the strategy).
+    fn build(low: T, up: T) -> Self::Iter;
+
+    /// Builds an iterator over as many of the values from `low` to `up` (both ends included) as
+    /// the strategy is able to yield, so the strategies can be compared by throughput.
+    fn covering(low: T, up: T) -> Self::Iter {
+        Self::build(low, up)
+    }
+}
+
+/// The non-inclusive range `low..up`.
+///
+/// It yields one element less than the others (except when it is [covering](RangeStrategy::covering)
+/// a range that ends below `T::MAX`), and serves as the baseline the inclusive strategies are
+/// compared against.
+pub enum NonInclusive {}
+
+impl<T: Integer> RangeStrategy<T> for NonInclusive
+where
+    Range<T>: DoubleEndedIterator<Item = T>,
+{
+    const NAME: &'static str = "non-inclusive";
+    type Iter = Range<T>;
+
+    fn build(low: T, up: T) -> Range<T> {
+        low..up
+    }
+
+    /// `low..up + 1`, unless `up` is `T::MAX`.
+    fn covering(low: T, up: T) -> Range<T> {
+        if up == T::MAX {
+            low..up
+        } else {
+            low..up.successor()
+        }
+    }
+}
+
+macro_rules! inclusive_strategy {
+    (
+        $(#[$meta:meta])* $strategy:ident, $name:expr, $iter:ident, $bound:ident
+        $(, closed_form: $closed_form:expr)?
+    ) => {
+        $(#[$meta])*
+        pub enum $strategy {}
+
+        impl<T: $bound> RangeStrategy<T> for $strategy
+        where
+            $iter<T>: DoubleEndedIterator<Item = T> + crate::InclusiveRange<T>,
+        {
+            const NAME: &'static str = $name;
+            $(const CLOSED_FORM: bool = $closed_form;)?
+            type Iter = $iter<T>;
+
+            fn build(low: T, up: T) -> $iter<T> {
+                crate::InclusiveRange::inclusive(low, up)
+            }
+        }
+    };
+}
+
+inclusive_strategy!(
+    /// The standard inclusive range `low..=up`.
+    Inclusive,
+    "inclusive",
+    RangeInclusive,
+    Integer
+);
+inclusive_strategy!(
+    /// [`DynamicInclusiveRange`].
+    Dynamic,
+    "dynamic",
+    DynamicInclusiveRange,
+    Integer
+);
+inclusive_strategy!(
+    /// [`FlaggedRange`].
+    Flagged,
+    "flagged",
+    FlaggedRange,
+    Integer
+);
+inclusive_strategy!(
+    /// [`DoWhileRange`].
+    DoWhile,
+    "do-while",
+    DoWhileRange,
+    Integer
+);
+inclusive_strategy!(
+    /// [`OptionEndRange`].
+    OptionEnd,
+    "option-end",
+    OptionEndRange,
+    Integer,
+    // The `Option` that holds the end is matched on every step, which LLVM doesn't see through.
+    closed_form: false
+);
+inclusive_strategy!(
+    /// [`WideRange`].
+    Wide,
+    "wide",
+    WideRange,
+    Narrow
+);
+
+/// A way of stepping through the values between two bounds.
+pub trait SteppedStrategy<T>: 'static {
+    /// A short name of the strategy to be used in benchmark ids.
+    const NAME: &'static str;
+
+    /// The iterator the strategy builds.
+    type Iter: Iterator<Item = T>;
+
+    /// Builds an iterator over `low`, `low + step`, `low + 2 * step` and so on while the values
+    /// don't exceed `up` (unless stated otherwise by the strategy).
+    fn build(low: T, up: T, step: usize) -> Self::Iter;
+}
+
+/// The range strategy `S` stepped through with [`Iterator::step_by`].
+pub struct StepBy<S>(PhantomData<S>);
+
+impl<T, S: RangeStrategy<T>> SteppedStrategy<T> for StepBy<S> {
+    const NAME: &'static str = S::NAME;
+    type Iter = iter::StepBy<S::Iter>;
+
+    fn build(low: T, up: T, step: usize) -> Self::Iter {
+        S::build(low, up).step_by(step)
+    }
+}
+
+/// [`DynamicSteppedRange`].
+pub enum DynamicStepped {}
+
+impl<T: Integer> SteppedStrategy<T> for DynamicStepped
+where
+    Range<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    const NAME: &'static str = "dynamic-stepped";
+    type Iter = DynamicSteppedRange<T>;
+
+    fn build(low: T, up: T, step: usize) -> DynamicSteppedRange<T> {
+        DynamicSteppedRange::new(low, up, step)
+    }
+}
+
+/// Something done with every strategy available for the type `T`.
+pub trait Visitor<T> {
+    /// Visits the strategy `S`.
+    fn visit<S: RangeStrategy<T>>(&mut self);
+}
+
+/// Something done with every stepped strategy available for the type `T`.
+pub trait SteppedVisitor<T> {
+    /// Visits the stepped strategy `S`.
+    fn visit<S: SteppedStrategy<T>>(&mut self);
+}
+
+/// The names of the strategies, or only of the ones summed in closed form, in the registry order.
+fn collect_names(closed_form_only: bool) -> Vec<&'static str> {
+    struct Names {
+        closed_form_only: bool,
+        names: Vec<&'static str>,
+    }
+
+    impl Visitor<u64> for Names {
+        fn visit<S: RangeStrategy<u64>>(&mut self) {
+            if S::CLOSED_FORM || !self.closed_form_only {
+                self.names.push(S::NAME);
+            }
+        }
+    }
+
+    impl SteppedVisitor<u64> for Names {
+        fn visit<S: SteppedStrategy<u64>>(&mut self) {
+            if !self.closed_form_only && !self.names.contains(&S::NAME) {
+                self.names.push(S::NAME);
+            }
+        }
+    }
+
+    // Every strategy is available for `u64`.
+    let mut names = Names {
+        closed_form_only,
+        names: Vec::new(),
+    };
+    u64::visit(&mut names);
+    u64::visit_stepped(&mut names);
+    names.names
+}
+
+/// The names of the strategies in the registry order, followed by the ones that only step through
+/// the ranges.
+pub fn names() -> Vec<&'static str> {
+    collect_names(false)
+}
+
+/// The names of the strategies whose ranges LLVM is known to sum in closed form, in the registry
+/// order.
+pub fn closed_form() -> Vec<&'static str> {
+    collect_names(true)
+}
+
+/// The registry of strategies: an integer type that knows which strategies are available for it.
+///
+/// Adding a strategy boils down to implementing [`RangeStrategy`] (or [`SteppedStrategy`] for one
+/// that only steps through the ranges) and listing it in `impl_strategies!` below.
+pub trait Strategies: Integer {
+    /// Calls `visitor` with every strategy available for the type, in a stable order.
+    fn visit<V: Visitor<Self>>(visitor: &mut V);
+
+    /// Calls `visitor` with every stepped strategy available for the type, in a stable order: the
+    /// strategies stepped through with [`StepBy`], then the ones that only step through the
+    /// ranges. None of them is summed in closed form, so they are only benched over bounded spans.
+    fn visit_stepped<V: SteppedVisitor<Self>>(visitor: &mut V);
+}
+
+macro_rules! impl_strategies {
+    ($($t:ty: $($strategy:ident)* | $($stepped:ident)*;)*) => {
+        $(
+            impl Strategies for $t {
+                fn visit<V: Visitor<Self>>(visitor: &mut V) {
+                    $(
+                        visitor.visit::<$strategy>();
+                    )*
+                }
+
+                fn visit_stepped<V: SteppedVisitor<Self>>(visitor: &mut V) {
+                    $(
+                        visitor.visit::<StepBy<$strategy>>();
+                    )*
+                    $(
+                        visitor.visit::<$stepped>();
+                    )*
+                }
+            }
+        )*
+    };
+}
+
+// The strategies after `|` only step through the ranges.
+impl_strategies! {
+    u8: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    u16: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    u32: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    u64: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    usize: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    i8: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    i16: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    i32: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    i64: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    isize: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd Wide | DynamicStepped;
+    // Nothing is wider than 128 bits, hence no `Wide`.
+    u128: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd | DynamicStepped;
+    i128: NonInclusive Inclusive Dynamic Flagged DoWhile OptionEnd | DynamicStepped;
+}
+
+/// Something done with every integer type of the registry.
+pub trait TypeVisitor {
+    /// Visits the integer type `T`.
+    fn visit<T: Strategies + FromStr>(&mut self);
+}
+
+/// Calls `visitor` with every integer type of the registry, in a stable order.
+pub fn visit_types<V: TypeVisitor>(visitor: &mut V) {
+    visitor.visit::<u8>();
+    visitor.visit::<u16>();
+    visitor.visit::<u32>();
+    visitor.visit::<u64>();
+    visitor.visit::<u128>();
+    visitor.visit::<usize>();
+    visitor.visit::<i8>();
+    visitor.visit::<i16>();
+    visitor.visit::<i32>();
+    visitor.visit::<i64>();
+    visitor.visit::<i128>();
+    visitor.visit::<isize>();
+}
@@ -0,0 +1,359 @@
+//! Summaries of the exported results: how every strategy compares to the non-inclusive range and
+//! to the other strategies, and how a run compares to a saved baseline run.
+
+use super::export::{Export, Record};
+use super::stats::{Comparison, SIGNIFICANCE_LEVEL};
+use super::strategy::{self, RangeStrategy};
+use std::collections::HashMap;
+
+/// The name of the strategy the others are compared against.
+pub const BASELINE: &str = <strategy::NonInclusive as RangeStrategy<u64>>::NAME;
+
+/// Whether `a` from the export `of_a` and `b` from `of_b` differ significantly: by Welch's t-test
+/// when the samples of both are known, and by the overlap of the confidence intervals of their
+/// means otherwise.
+pub fn significant(of_a: &Export, a: &Record, of_b: &Export, b: &Record) -> bool {
+    match (of_a.samples_of(a), of_b.samples_of(b)) {
+        (Some(a), Some(b)) if a.len() > 1 && b.len() > 1 => Comparison::new(a, b).significant(),
+        _ => a.mean_lower > b.mean_upper || a.mean_upper < b.mean_lower,
+    }
+}
+
+/// Formats a time in nanoseconds with a suitable unit.
+pub fn format_time(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{:.2} ns", ns)
+    } else if ns < 1e6 {
+        format!("{:.2} us", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.2} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+/// The results of every strategy for a single workload and pair of bounds within a group.
+struct Case<'a> {
+    label: String,
+    records: HashMap<&'a str, &'a Record>,
+}
+
+/// A group of results printed as a single table.
+struct Table<'a> {
+    group: &'a str,
+    strategies: Vec<&'a str>,
+    cases: Vec<Case<'a>>,
+}
+
+/// Splits the results into tables by group and into rows by workload and bounds, keeping the order
+/// of the results.
+fn tables(results: &[Record]) -> Vec<Table<'_>> {
+    let order = strategy::names();
+    let mut tables: Vec<Table> = Vec::new();
+    for record in results {
+        let table = match tables.iter().position(|table| table.group == record.group) {
+            Some(index) => &mut tables[index],
+            None => {
+                tables.push(Table {
+                    group: &record.group,
+                    strategies: Vec::new(),
+                    cases: Vec::new(),
+                });
+                tables.last_mut().unwrap()
+            }
+        };
+        if !table.strategies.contains(&record.strategy.as_str()) {
+            table.strategies.push(&record.strategy);
+        }
+        let label = format!("{} {}..={}", record.workload, record.low, record.up);
+        let case = match table.cases.iter().position(|case| case.label == label) {
+            Some(index) => &mut table.cases[index],
+            None => {
+                table.cases.push(Case {
+                    label,
+                    records: HashMap::new(),
+                });
+                table.cases.last_mut().unwrap()
+            }
+        };
+        case.records.insert(&record.strategy, record);
+    }
+    for table in &mut tables {
+        // The strategies unknown to the registry go last.
+        table.strategies.sort_by_key(|name| {
+            order
+                .iter()
+                .position(|known| known == name)
+                .unwrap_or(order.len())
+        });
+    }
+    tables
+}
+
+/// Returns the geometric mean of the time of every strategy relative to the non-inclusive range,
+/// over all the benchmarks measured with both, in the order of the strategies.
+pub fn mean_ratios(export: &Export) -> Vec<(String, f64)> {
+    let mut sums: Vec<(String, f64, u32)> = Vec::new();
+    for table in tables(&export.results) {
+        for case in &table.cases {
+            let baseline = match case.records.get(BASELINE) {
+                Some(baseline) => baseline,
+                None => continue,
+            };
+            for name in &table.strategies {
+                let record = match case.records.get(name) {
+                    Some(record) if *name != BASELINE => record,
+                    _ => continue,
+                };
+                let ln_ratio = (record.mean / baseline.mean).ln();
+                match sums.iter_mut().find(|(known, ..)| known == name) {
+                    Some((_, sum, count)) => {
+                        *sum += ln_ratio;
+                        *count += 1;
+                    }
+                    None => sums.push((name.to_string(), ln_ratio, 1)),
+                }
+            }
+        }
+    }
+    sums.into_iter()
+        .map(|(name, sum, count)| (name, (sum / f64::from(count)).exp()))
+        .collect()
+}
+
+/// Prints a table per group with the time of the non-inclusive range and the time of every other
+/// strategy relative to it.
+pub fn print(export: &Export) {
+    for table in tables(&export.results) {
+        let others: Vec<&str> = table
+            .strategies
+            .iter()
+            .copied()
+            .filter(|&name| name != BASELINE)
+            .collect();
+        let label_width = table
+            .cases
+            .iter()
+            .map(|case| case.label.len())
+            .chain(Some(table.group.len()))
+            .max()
+            .unwrap_or(0);
+        print!(
+            "{:<width$} {:>12}",
+            table.group,
+            BASELINE,
+            width = label_width
+        );
+        for name in &others {
+            print!(" {:>16}", name);
+        }
+        println!();
+        for case in &table.cases {
+            print!("{:<width$}", case.label, width = label_width);
+            let baseline = case.records.get(BASELINE);
+            match baseline {
+                Some(baseline) => print!(" {:>12}", format_time(baseline.mean)),
+                None => print!(" {:>12}", "-"),
+            }
+            for name in &others {
+                let cell = match (case.records.get(name), baseline) {
+                    (Some(record), Some(baseline)) => format!(
+                        "{:.2}x{}",
+                        record.mean / baseline.mean,
+                        if significant(export, record, export, baseline) {
+                            "*"
+                        } else {
+                            " "
+                        }
+                    ),
+                    (Some(record), None) => format_time(record.mean),
+                    (None, _) => "-".to_owned(),
+                };
+                print!(" {:>16}", cell);
+            }
+            println!();
+        }
+        println!();
+    }
+    println!(
+        "The times are relative to the {} range; * marks the significant differences.",
+        BASELINE
+    );
+}
+
+/// Prints the hardware counters per iteration of every benchmark that has them.
+pub fn print_counters(export: &Export) {
+    let counted: Vec<&Record> = export
+        .results
+        .iter()
+        .filter(|record| {
+            record.instructions.is_some()
+                || record.cycles.is_some()
+                || record.branches.is_some()
+                || record.branch_misses.is_some()
+        })
+        .collect();
+    if counted.is_empty() {
+        return;
+    }
+    let width = counted
+        .iter()
+        .map(|record| record.id.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:<width$} {:>12} {:>14} {:>14} {:>6} {:>14} {:>14}",
+        "benchmark",
+        "time",
+        "instructions",
+        "cycles",
+        "ipc",
+        "branches",
+        "branch misses",
+        width = width
+    );
+    let count = |value: Option<f64>| value.map_or_else(|| "-".to_owned(), |v| format!("{:.1}", v));
+    for record in counted {
+        let ipc = match (record.instructions, record.cycles) {
+            (Some(instructions), Some(cycles)) if cycles > 0. => {
+                format!("{:.2}", instructions / cycles)
+            }
+            _ => "-".to_owned(),
+        };
+        println!(
+            "{:<width$} {:>12} {:>14} {:>14} {:>6} {:>14} {:>14}",
+            record.id,
+            format_time(record.mean),
+            count(record.instructions),
+            count(record.cycles),
+            ipc,
+            count(record.branches),
+            count(record.branch_misses),
+            width = width
+        );
+    }
+    println!();
+}
+
+/// Prints how every result of `current` compares to the same benchmark in `baseline`.
+///
+/// Returns the number of benchmarks that got significantly slower.
+pub fn compare(baseline: &Export, current: &Export) -> usize {
+    let saved: HashMap<&str, &Record> = baseline
+        .results
+        .iter()
+        .map(|record| (record.id.as_str(), record))
+        .collect();
+    let width = current
+        .results
+        .iter()
+        .map(|record| record.id.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:<width$} {:>12} {:>12} {:>9}",
+        "benchmark",
+        "baseline",
+        "current",
+        "change",
+        width = width
+    );
+    let mut regressions = 0;
+    for record in &current.results {
+        let old = match saved.get(record.id.as_str()) {
+            Some(old) => old,
+            None => continue,
+        };
+        let change = (record.mean / old.mean - 1.) * 100.;
+        let verdict = match (
+            significant(current, record, baseline, old),
+            record.mean > old.mean,
+        ) {
+            (false, _) => "",
+            (true, true) => {
+                regressions += 1;
+                "regressed"
+            }
+            (true, false) => "improved",
+        };
+        println!(
+            "{:<width$} {:>12} {:>12} {:>+8.1}% {}",
+            record.id,
+            format_time(old.mean),
+            format_time(record.mean),
+            change,
+            verdict,
+            width = width
+        );
+    }
+    println!(
+        "{} of {} benchmarks regressed since the baseline run",
+        regressions,
+        current.results.len()
+    );
+    if let (Some(old), Some(new)) = (&baseline.environment, &current.environment) {
+        let differences = [
+            ("rustc", &old.rustc, &new.rustc),
+            ("target", &old.target, &new.target),
+            ("opt-level", &old.opt_level, &new.opt_level),
+            ("rustflags", &old.rustflags, &new.rustflags),
+        ];
+        for (name, old, new) in differences.iter().filter(|(_, old, new)| old != new) {
+            println!(
+                "warning: the {} differs: {} in the baseline run, {} now",
+                name, old, new
+            );
+        }
+        if old.cpu != new.cpu {
+            println!("warning: the runs were made on different CPUs");
+        }
+    }
+    regressions
+}
+
+/// Prints the pairwise comparison of the strategies for every workload and pair of bounds: the
+/// ratio of their mean times, Welch's t-test and Cohen's d.
+pub fn pairwise(export: &Export) {
+    for table in tables(&export.results) {
+        for case in &table.cases {
+            println!("{} {}", table.group, case.label);
+            for (i, a) in table.strategies.iter().enumerate() {
+                for b in &table.strategies[i + 1..] {
+                    let samples = |name| {
+                        case.records
+                            .get(name)
+                            .and_then(|record| export.samples_of(record))
+                            .filter(|samples| samples.len() > 1)
+                    };
+                    let (sa, sb) = match (samples(a), samples(b)) {
+                        (Some(sa), Some(sb)) => (sa, sb),
+                        _ => continue,
+                    };
+                    let comparison = Comparison::new(sb, sa);
+                    println!(
+                        "  {:>16} vs {:<16} {:>7.3}x  t {:>8.2}  df {:>6.1}  p {:.4}  d {:>6.2} \
+                         {:<10}  {}",
+                        b,
+                        a,
+                        comparison.ratio,
+                        comparison.welch.t,
+                        comparison.welch.df,
+                        comparison.welch.p,
+                        comparison.d,
+                        comparison.effect().name(),
+                        if comparison.significant() {
+                            "significant"
+                        } else {
+                            "noise"
+                        }
+                    );
+                }
+            }
+        }
+    }
+    println!(
+        "The ratios are of the mean times; the differences with p < {} are significant, and d is \
+         the effect size (Cohen's d).",
+        SIGNIFICANCE_LEVEL
+    );
+}
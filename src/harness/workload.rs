@@ -0,0 +1,275 @@
+//! What the benchmarks do with the values yielded by a range.
+
+use super::{calc, sum_for, sum_for_each, sum_while_let};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// The length of the buffers used by [`Index`] and [`Write`]. A power of two, so a value is turned
+/// into an index by masking.
+const BUFFER_LEN: usize = 1 << 12;
+
+/// A piece of work done over a `u64` range.
+pub trait Workload: Clone + 'static {
+    /// The result of the work, which is black-boxed by the benchmark.
+    type Output;
+
+    /// A short name of the workload to be used in benchmark ids.
+    fn name(&self) -> &'static str;
+
+    /// Consumes `iter`, which yields the values of a range with the inclusive upper bound `up`.
+    fn run<I: Iterator<Item = u64>>(&mut self, up: u64, iter: I) -> Self::Output;
+}
+
+/// Something done with every workload of the library.
+pub trait Visitor {
+    /// Visits `workload`.
+    fn visit<W: Workload>(&mut self, workload: W);
+}
+
+/// Calls `visitor` with every workload of the library, in a stable order.
+///
+/// The iteration styles ([`ForLoop`], [`WhileLet`] and [`ForEach`]) are not included since they
+/// only differ from [`Sum`] in how the range is consumed.
+pub fn visit<V: Visitor>(visitor: &mut V) {
+    visitor.visit(Sum);
+    visitor.visit(Index::default());
+    visitor.visit(Write::default());
+    visitor.visit(Hash);
+    visitor.visit(FilterMap);
+    visitor.visit(Find);
+    visitor.visit(Position);
+    visitor.visit(Polynomial);
+}
+
+/// How the values of a range are iterated over when they are summed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Iteration {
+    /// Internal iteration with [`Iterator::fold`], as done by [`calc`].
+    Fold,
+    /// External iteration with a `for` loop.
+    For,
+    /// External iteration with a `while let Some(_) = iter.next()` loop.
+    WhileLet,
+    /// Internal iteration with [`Iterator::for_each`].
+    ForEach,
+}
+
+impl Iteration {
+    /// Every iteration style.
+    pub const ALL: [Iteration; 4] = [
+        Iteration::Fold,
+        Iteration::For,
+        Iteration::WhileLet,
+        Iteration::ForEach,
+    ];
+
+    /// The name of the iteration style.
+    pub fn name(self) -> &'static str {
+        match self {
+            Iteration::Fold => "fold",
+            Iteration::For => "for",
+            Iteration::WhileLet => "while-let",
+            Iteration::ForEach => "for-each",
+        }
+    }
+}
+
+/// Sums the values with [`calc`], which LLVM is likely to turn into a closed-form formula.
+#[derive(Clone)]
+pub struct Sum;
+
+impl Workload for Sum {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "sum"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        calc(iter)
+    }
+}
+
+/// Sums the elements of a slice indexed by the values.
+#[derive(Clone)]
+pub struct Index(Vec<u64>);
+
+impl Default for Index {
+    fn default() -> Self {
+        Index((0..BUFFER_LEN as u64).collect())
+    }
+}
+
+impl Workload for Index {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "index"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        let data = &self.0[..BUFFER_LEN];
+        iter.fold(0, |acc, i| {
+            acc.wrapping_add(data[i as usize & (BUFFER_LEN - 1)])
+        })
+    }
+}
+
+/// Writes the values into a buffer at the positions given by the values themselves.
+#[derive(Clone)]
+pub struct Write(Vec<u64>);
+
+impl Default for Write {
+    fn default() -> Self {
+        Write(vec![0; BUFFER_LEN])
+    }
+}
+
+impl Workload for Write {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "write"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        let data = &mut self.0[..BUFFER_LEN];
+        for i in iter {
+            data[i as usize & (BUFFER_LEN - 1)] = i;
+        }
+        data[0]
+    }
+}
+
+/// Feeds the values into the standard library's hasher.
+#[derive(Clone)]
+pub struct Hash;
+
+impl Workload for Hash {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "hash"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        iter.for_each(|i| hasher.write_u64(i));
+        hasher.finish()
+    }
+}
+
+/// Sums the values that pass a filter after mapping them.
+#[derive(Clone)]
+pub struct FilterMap;
+
+impl Workload for FilterMap {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "filter-map"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        iter.filter(|i| i % 3 != 0)
+            .map(|i| i.wrapping_mul(7))
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+/// Looks for the upper bound with [`Iterator::find`], which exits early but only at the very end.
+#[derive(Clone)]
+pub struct Find;
+
+impl Workload for Find {
+    type Output = Option<u64>;
+
+    fn name(&self) -> &'static str {
+        "find"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, up: u64, mut iter: I) -> Option<u64> {
+        iter.find(|&i| i == up)
+    }
+}
+
+/// Looks for the position of the upper bound with [`Iterator::position`].
+#[derive(Clone)]
+pub struct Position;
+
+impl Workload for Position {
+    type Output = Option<usize>;
+
+    fn name(&self) -> &'static str {
+        "position"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, up: u64, mut iter: I) -> Option<usize> {
+        iter.position(|i| i == up)
+    }
+}
+
+/// Accumulates the values into a polynomial hash, which can be neither reordered nor vectorized.
+#[derive(Clone)]
+pub struct Polynomial;
+
+impl Workload for Polynomial {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "polynomial"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        iter.fold(0, |acc, i| acc.wrapping_mul(31).wrapping_add(i))
+    }
+}
+
+/// Sums the values with a plain `for` loop, which repeatedly calls [`Iterator::next`].
+#[derive(Clone)]
+pub struct ForLoop;
+
+impl Workload for ForLoop {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "for"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        sum_for(iter)
+    }
+}
+
+/// Sums the values with an explicit `while let Some(_) = iter.next()` loop.
+#[derive(Clone)]
+pub struct WhileLet;
+
+impl Workload for WhileLet {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "while-let"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        sum_while_let(iter)
+    }
+}
+
+/// Sums the values with [`Iterator::for_each`], which is internal iteration just like
+/// [`Sum`]'s fold.
+#[derive(Clone)]
+pub struct ForEach;
+
+impl Workload for ForEach {
+    type Output = u64;
+
+    fn name(&self) -> &'static str {
+        "for-each"
+    }
+
+    fn run<I: Iterator<Item = u64>>(&mut self, _up: u64, iter: I) -> u64 {
+        sum_for_each(iter)
+    }
+}
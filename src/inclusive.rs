@@ -0,0 +1,31 @@
+use crate::Integer;
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+
+/// A range-like iterator that can be built from a pair of inclusive bounds.
+///
+/// This is the common interface of all the inclusive range strategies, which lets the benchmarks
+/// treat them uniformly.
+pub trait InclusiveRange<T>: Iterator<Item = T> {
+    /// Initializes a range that yields every value from `from` to `inclusive_to`, both ends
+    /// included.
+    fn inclusive(from: T, inclusive_to: T) -> Self;
+}
+
+impl<T> InclusiveRange<T> for RangeInclusive<T>
+where
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        from..=inclusive_to
+    }
+}
+
+/// Returns the size hint of a non-empty inclusive range.
+pub(crate) fn inclusive_size_hint<T: Integer>(from: T, inclusive_to: T) -> (usize, Option<usize>) {
+    let len = from.distance(inclusive_to).checked_add(1);
+    match len.map(usize::try_from) {
+        Some(Ok(len)) => (len, Some(len)),
+        _ => (usize::MAX, None),
+    }
+}
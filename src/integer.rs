@@ -0,0 +1,90 @@
+use std::fmt::{Debug, Display};
+
+/// A primitive integer type that a [`DynamicInclusiveRange`](crate::DynamicInclusiveRange) can be
+/// built over.
+///
+/// The trait is sealed and is implemented for every primitive integer type.
+pub trait Integer: Copy + Ord + Display + Debug + 'static + private::Sealed {
+    /// The smallest value of the type.
+    const MIN: Self;
+    /// The largest value of the type.
+    const MAX: Self;
+    /// Zero.
+    const ZERO: Self;
+
+    /// Returns `self + 1`. Must not be called on `Self::MAX`.
+    fn successor(self) -> Self;
+
+    /// Returns `self - 1`. Must not be called on `Self::MIN`.
+    fn predecessor(self) -> Self;
+
+    /// Adds two values wrapping around at the boundary of the type.
+    fn wrapping_add(self, other: Self) -> Self;
+
+    /// Returns the number of steps of `1` it takes to get from `self` to `end`. Must not be called
+    /// with `end < self`.
+    fn distance(self, end: Self) -> u128;
+
+    /// Returns `self + n`. Must not be called when the result doesn't fit into the type.
+    fn forward(self, n: u128) -> Self;
+}
+
+/// An [`Integer`] that is narrower than 128 bits, so every value of the type and the distance
+/// between any two of them fit into `u128` with room to spare.
+pub trait Narrow: Integer {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl Integer for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn successor(self) -> Self {
+                    self + 1
+                }
+
+                #[inline]
+                fn predecessor(self) -> Self {
+                    self - 1
+                }
+
+                #[inline]
+                fn wrapping_add(self, other: Self) -> Self {
+                    <$t>::wrapping_add(self, other)
+                }
+
+                #[inline]
+                fn distance(self, end: Self) -> u128 {
+                    // Sign extension is undone by the wrapping subtraction as long as `end >= self`.
+                    (end as u128).wrapping_sub(self as u128)
+                }
+
+                #[inline]
+                fn forward(self, n: u128) -> Self {
+                    (self as u128).wrapping_add(n) as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+macro_rules! impl_narrow {
+    ($($t:ty)*) => {
+        $(
+            impl Narrow for $t {}
+        )*
+    };
+}
+
+impl_narrow!(u8 u16 u32 u64 usize i8 i16 i32 i64 isize);
@@ -0,0 +1,35 @@
+//! Range-like iterators that try to beat the performance of `RangeInclusive`.
+//!
+//! [`DynamicInclusiveRange`] decides at construction whether it can go with a plain `Range` under
+//! the hood, and falls back to `RangeInclusive` only when the upper bound is the largest value of
+//! the type. [`DynamicSteppedRange`] does the same for `(from..=to).step_by(step)`.
+//!
+//! A few alternative designs are provided for comparison: [`FlaggedRange`], [`WideRange`],
+//! [`DoWhileRange`] and [`OptionEndRange`]. All the inclusive strategies implement
+//! [`InclusiveRange`].
+//!
+//! The `harness` feature (enabled by default) additionally provides the [`criterion`] benchmarks
+//! that compare the strategies; disable default features to depend on the ranges alone.
+//!
+//! [`criterion`]: https://docs.rs/criterion
+
+mod do_while;
+mod dynamic;
+mod flagged;
+mod inclusive;
+mod integer;
+mod option_end;
+mod stepped;
+mod wide;
+
+#[cfg(feature = "harness")]
+pub mod harness;
+
+pub use crate::do_while::DoWhileRange;
+pub use crate::dynamic::DynamicInclusiveRange;
+pub use crate::flagged::FlaggedRange;
+pub use crate::inclusive::InclusiveRange;
+pub use crate::integer::{Integer, Narrow};
+pub use crate::option_end::OptionEndRange;
+pub use crate::stepped::DynamicSteppedRange;
+pub use crate::wide::WideRange;
@@ -0,0 +1,71 @@
+use crate::inclusive::inclusive_size_hint;
+use crate::{InclusiveRange, Integer};
+use std::iter::FusedIterator;
+
+/// A range with an exclusive upper bound, where `None` stands for the bound right after `T::MAX`.
+///
+/// As long as the upper bound is below `T::MAX` the range works exactly like `Range`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionEndRange<T> {
+    start: T,
+    end: Option<T>,
+}
+
+impl<T: Integer> OptionEndRange<T> {
+    /// Initializes a range that yields every value from `from` to `inclusive_to`, both ends
+    /// included.
+    pub fn new(from: T, inclusive_to: T) -> Self {
+        let end = if from > inclusive_to {
+            Some(from)
+        } else if inclusive_to == T::MAX {
+            None
+        } else {
+            Some(inclusive_to.successor())
+        };
+        OptionEndRange { start: from, end }
+    }
+}
+
+impl<T: Integer> Iterator for OptionEndRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = self.start;
+        match self.end {
+            Some(end) if value < end => self.start = value.successor(),
+            Some(_) => return None,
+            // The last value has been reached, so the end becomes an ordinary exclusive bound.
+            None if value == T::MAX => self.end = Some(value),
+            None => self.start = value.successor(),
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.end {
+            Some(end) if self.start < end => inclusive_size_hint(self.start, end.predecessor()),
+            Some(_) => (0, Some(0)),
+            None => inclusive_size_hint(self.start, T::MAX),
+        }
+    }
+}
+
+impl<T: Integer> DoubleEndedIterator for OptionEndRange<T> {
+    fn next_back(&mut self) -> Option<T> {
+        let value = match self.end {
+            Some(end) if self.start < end => end.predecessor(),
+            Some(_) => return None,
+            None => T::MAX,
+        };
+        self.end = Some(value);
+        Some(value)
+    }
+}
+
+impl<T: Integer> FusedIterator for OptionEndRange<T> {}
+
+impl<T: Integer> InclusiveRange<T> for OptionEndRange<T> {
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        OptionEndRange::new(from, inclusive_to)
+    }
+}
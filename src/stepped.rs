@@ -0,0 +1,84 @@
+use crate::Integer;
+use std::iter::StepBy;
+use std::ops::{Range, RangeInclusive};
+
+/// A stepped counterpart of [`DynamicInclusiveRange`](crate::DynamicInclusiveRange) that yields the
+/// same values as `(from..=to).step_by(step)`.
+///
+/// The upper bound is first lowered to the last value that is actually yielded, so an inclusive
+/// range is only needed when that value is the largest value of the type. For instance,
+/// `0..=u64::MAX` with a step of `2` ends at `u64::MAX - 1` and is iterated as a non-inclusive
+/// range.
+#[derive(Debug, Clone)]
+pub enum DynamicSteppedRange<T> {
+    /// The last yielded value is `T::MAX`, so an inclusive range is the only option.
+    Inclusive(StepBy<RangeInclusive<T>>),
+    /// The last yielded value is below `T::MAX` and the upper bound has been turned into an
+    /// exclusive one.
+    NonInclusive(StepBy<Range<T>>),
+}
+
+impl<T: Integer> DynamicSteppedRange<T>
+where
+    Range<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    /// Initializes a stepped range that yields `from`, `from + step`, `from + 2 * step` and so on
+    /// while the values do not exceed `inclusive_to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero, just like [`Iterator::step_by`].
+    pub fn new(from: T, inclusive_to: T, step: usize) -> Self {
+        assert!(step != 0, "step must be non-zero");
+        if from > inclusive_to {
+            return DynamicSteppedRange::NonInclusive((from..from).step_by(step));
+        }
+        let span = from.distance(inclusive_to);
+        let last = from.forward(span - span % step as u128);
+        if last == T::MAX {
+            DynamicSteppedRange::Inclusive((from..=last).step_by(step))
+        } else {
+            DynamicSteppedRange::NonInclusive((from..last.successor()).step_by(step))
+        }
+    }
+}
+
+impl<T> Iterator for DynamicSteppedRange<T>
+where
+    Range<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            DynamicSteppedRange::Inclusive(r) => r.next(),
+            DynamicSteppedRange::NonInclusive(r) => r.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            DynamicSteppedRange::Inclusive(r) => r.size_hint(),
+            DynamicSteppedRange::NonInclusive(r) => r.size_hint(),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        match self {
+            DynamicSteppedRange::Inclusive(r) => r.nth(n),
+            DynamicSteppedRange::NonInclusive(r) => r.nth(n),
+        }
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, T) -> B,
+    {
+        match self {
+            DynamicSteppedRange::Inclusive(r) => r.fold(init, f),
+            DynamicSteppedRange::NonInclusive(r) => r.fold(init, f),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{InclusiveRange, Narrow};
+use std::convert::TryFrom;
+use std::iter::FusedIterator;
+
+/// An inclusive range that counts in `u128` internally, where the exclusive upper bound
+/// `inclusive_to + 1` never overflows.
+///
+/// Only available for the types narrower than 128 bits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WideRange<T> {
+    /// The value the offsets are counted from.
+    base: T,
+    /// The offset of the next value from `base`.
+    next: u128,
+    /// The offset of the value right after the last one.
+    end: u128,
+}
+
+impl<T: Narrow> WideRange<T> {
+    /// Initializes a range that yields every value from `from` to `inclusive_to`, both ends
+    /// included.
+    pub fn new(from: T, inclusive_to: T) -> Self {
+        let end = if from > inclusive_to {
+            0
+        } else {
+            from.distance(inclusive_to) + 1
+        };
+        WideRange {
+            base: from,
+            next: 0,
+            end,
+        }
+    }
+}
+
+impl<T: Narrow> Iterator for WideRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next < self.end {
+            let value = self.base.forward(self.next);
+            self.next += 1;
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The length is at most `2^64`, so it doesn't fit into `usize` only in the single case of
+        // the full `u64` domain on a 64-bit platform.
+        match usize::try_from(self.end - self.next) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl<T: Narrow> DoubleEndedIterator for WideRange<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.next < self.end {
+            self.end -= 1;
+            Some(self.base.forward(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Narrow> FusedIterator for WideRange<T> {}
+
+impl<T: Narrow> InclusiveRange<T> for WideRange<T> {
+    fn inclusive(from: T, inclusive_to: T) -> Self {
+        WideRange::new(from, inclusive_to)
+    }
+}
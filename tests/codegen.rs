@@ -0,0 +1,95 @@
+//! Codegen regression tests in the spirit of rustc's codegen tests: the summing loop of every
+//! strategy is compiled with `--emit=asm,llvm-ir` and the shape of the emitted code is checked.
+
+#![cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+
+use range_perf::harness::codegen::{self, Artifacts, AsmStats, ENTRY_POINTS};
+use range_perf::harness::export;
+use std::sync::OnceLock;
+
+/// Builds the library once for all the tests.
+fn artifacts() -> &'static Artifacts {
+    static ARTIFACTS: OnceLock<Artifacts> = OnceLock::new();
+    ARTIFACTS.get_or_init(|| {
+        let target_dir = export::target_dir().join("codegen");
+        codegen::emit(&target_dir).expect("Failed to emit the code")
+    })
+}
+
+fn symbol(strategy: &str) -> &'static str {
+    ENTRY_POINTS
+        .iter()
+        .find(|entry| entry.strategy == strategy)
+        .unwrap_or_else(|| panic!("No entry point for {}", strategy))
+        .symbol
+}
+
+fn asm(strategy: &str) -> String {
+    codegen::function_asm(&artifacts().asm, symbol(strategy))
+        .unwrap_or_else(|| panic!("No assembly for {}", strategy))
+}
+
+fn ir(strategy: &str) -> String {
+    codegen::function_ir(&artifacts().ir, symbol(strategy))
+        .unwrap_or_else(|| panic!("No IR for {}", strategy))
+}
+
+#[test]
+fn every_entry_point_is_emitted() {
+    for entry in ENTRY_POINTS {
+        asm(entry.strategy);
+        ir(entry.strategy);
+    }
+}
+
+#[test]
+fn non_inclusive_is_closed_form_or_vectorized() {
+    let stats = codegen::analyze(&asm("non-inclusive"));
+    assert!(stats.loops == 0 || stats.vectorized, "{:?}", stats);
+}
+
+#[test]
+fn dynamic_is_closed_form_or_vectorized() {
+    let stats = codegen::analyze(&asm("dynamic"));
+    assert!(stats.loops == 0 || stats.vectorized, "{:?}", stats);
+}
+
+#[test]
+fn dynamic_has_no_branches_in_loops() {
+    let stats = codegen::analyze(&asm("dynamic"));
+    assert_eq!(stats.loop_branches, 0, "{:?}", stats);
+}
+
+#[test]
+fn dynamic_carries_no_exhausted_flag() {
+    // A boolean that is carried from one iteration to the next is the `exhausted` flag of
+    // `RangeInclusive` (or something equally bad).
+    let ir = ir("dynamic");
+    assert!(!ir.contains("phi i1"), "{}", ir);
+}
+
+#[test]
+fn analyze_counts_loops_and_branches() {
+    let asm = "\
+func:
+	xorl	%eax, %eax
+.LBB0_1:
+	addq	%rdi, %rax
+	testb	$1, %dl
+	jne	.LBB0_3
+	incq	%rdi
+	cmpq	%rsi, %rdi
+	jb	.LBB0_1
+.LBB0_3:
+	retq
+";
+    assert_eq!(
+        codegen::analyze(asm),
+        AsmStats {
+            instructions: 8,
+            loops: 1,
+            loop_branches: 1,
+            vectorized: false,
+        }
+    );
+}
@@ -0,0 +1,57 @@
+//! The scaffolding shared by the tests of the dynamic ranges, which compare them with the ranges
+//! of the standard library.
+
+use proptest::prelude::*;
+use std::fmt::Debug;
+
+/// How many values are compared at the start of a range that is too long to be walked through.
+pub const PREFIX: usize = 64;
+
+/// Checks that `actual` yields exactly the same values as `expected`, with the same size hints,
+/// by walking through both iterators completely.
+pub fn assert_same<I, J>(actual: I, expected: J)
+where
+    I: Iterator + Clone,
+    J: Iterator<Item = I::Item> + Clone,
+    I::Item: Debug + PartialEq,
+{
+    assert_eq!(
+        actual.clone().collect::<Vec<_>>(),
+        expected.clone().collect::<Vec<_>>()
+    );
+    assert_eq!(actual.clone().count(), expected.clone().count());
+    assert_eq!(actual.clone().last(), expected.clone().last());
+
+    let (mut actual, mut expected) = (actual, expected);
+    loop {
+        assert_eq!(actual.size_hint(), expected.size_hint());
+        let value = expected.next();
+        assert_eq!(actual.next(), value);
+        if value.is_none() {
+            break;
+        }
+    }
+}
+
+/// Checks that `actual` starts with the same values as `expected`, with the same size hints,
+/// without walking through the whole iterators.
+pub fn assert_same_start<I, J>(actual: I, expected: J)
+where
+    I: Iterator + Clone,
+    J: Iterator<Item = I::Item> + Clone,
+    I::Item: Debug + PartialEq,
+{
+    assert_eq!(actual.size_hint(), expected.size_hint());
+    assert_eq!(actual.clone().nth(PREFIX), expected.clone().nth(PREFIX));
+
+    let (mut actual, mut expected) = (actual, expected);
+    for _ in 0..PREFIX {
+        assert_eq!(actual.next(), expected.next());
+        assert_eq!(actual.size_hint(), expected.size_hint());
+    }
+}
+
+/// Generates a value that is either arbitrary or close to `u64::MAX`.
+pub fn near_max() -> impl Strategy<Value = u64> {
+    prop_oneof![any::<u64>(), (u64::MAX - 2 * PREFIX as u64)..=u64::MAX]
+}
@@ -0,0 +1,244 @@
+mod common;
+
+use common::{near_max, PREFIX};
+use proptest::prelude::*;
+use range_perf::{DynamicInclusiveRange, Integer};
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+/// Checks that `dynamic` yields exactly the same values as `expected` in both directions, with the
+/// same size hints, by walking through both ranges completely.
+fn assert_same<T>(dynamic: DynamicInclusiveRange<T>, expected: RangeInclusive<T>)
+where
+    T: Copy + Debug + Ord,
+    DynamicInclusiveRange<T>: DoubleEndedIterator<Item = T> + Clone,
+    RangeInclusive<T>: DoubleEndedIterator<Item = T> + Clone,
+{
+    assert_same_min_max(dynamic.clone(), expected.clone());
+    let mut values = Vec::new();
+    dynamic.clone().for_each(|value| values.push(value));
+    assert_eq!(values, expected.clone().collect::<Vec<_>>());
+
+    common::assert_same(dynamic.clone(), expected.clone());
+    common::assert_same(dynamic.rev(), expected.rev());
+}
+
+/// Checks the `min` and `max` of `dynamic` against the ones of `expected`, which don't walk
+/// through the ranges.
+fn assert_same_min_max<T>(dynamic: DynamicInclusiveRange<T>, expected: RangeInclusive<T>)
+where
+    T: Copy + Debug + Ord,
+    DynamicInclusiveRange<T>: Iterator<Item = T> + Clone,
+    RangeInclusive<T>: Iterator<Item = T> + Clone,
+{
+    assert_eq!(dynamic.clone().min(), expected.clone().min());
+    assert_eq!(dynamic.max(), expected.max());
+}
+
+/// Checks that `dynamic` starts and ends with the same values as `expected`, with the same size
+/// hints, without walking through the whole ranges.
+fn assert_same_ends<T>(dynamic: DynamicInclusiveRange<T>, expected: RangeInclusive<T>)
+where
+    T: Copy + Debug + Ord,
+    DynamicInclusiveRange<T>: DoubleEndedIterator<Item = T> + Clone,
+    RangeInclusive<T>: DoubleEndedIterator<Item = T> + Clone,
+{
+    assert_same_min_max(dynamic.clone(), expected.clone());
+    common::assert_same_start(dynamic.clone(), expected.clone());
+    common::assert_same_start(dynamic.clone().rev(), expected.clone().rev());
+    assert_eq!(dynamic.clone().last(), expected.clone().last());
+
+    let (mut dynamic, mut expected) = (dynamic, expected);
+    for _ in 0..PREFIX {
+        assert_eq!(dynamic.next(), expected.next());
+        assert_eq!(dynamic.next_back(), expected.next_back());
+        assert_eq!(dynamic.size_hint(), expected.size_hint());
+    }
+}
+
+#[test]
+fn all_u8_pairs() {
+    for from in u8::MIN..=u8::MAX {
+        for to in u8::MIN..=u8::MAX {
+            assert_same(DynamicInclusiveRange::new(from, to), from..=to);
+            assert_eq!(
+                DynamicInclusiveRange::new(from, to).len(),
+                (from..=to).len()
+            );
+        }
+    }
+}
+
+#[test]
+fn all_i8_pairs() {
+    for from in i8::MIN..=i8::MAX {
+        for to in i8::MIN..=i8::MAX {
+            assert_same(DynamicInclusiveRange::new(from, to), from..=to);
+            assert_eq!(
+                DynamicInclusiveRange::new(from, to).len(),
+                (from..=to).len()
+            );
+        }
+    }
+}
+
+#[test]
+fn max_edges() {
+    assert_same_ends(DynamicInclusiveRange::new(0, u64::MAX), 0..=u64::MAX);
+    assert_same_ends(DynamicInclusiveRange::new(1, u64::MAX), 1..=u64::MAX);
+    assert_same(
+        DynamicInclusiveRange::new(u64::MAX, u64::MAX),
+        u64::MAX..=u64::MAX,
+    );
+    let (from, to) = (u64::MAX, u64::MAX - 1);
+    assert_same(DynamicInclusiveRange::new(from, to), from..=to);
+    assert_same_ends(
+        DynamicInclusiveRange::new(i128::MIN, i128::MAX),
+        i128::MIN..=i128::MAX,
+    );
+}
+
+/// Checks that `dynamic` sums to the same value as `expected`, unless summing the range overflows
+/// `T`, which panics or wraps around depending on the build.
+fn assert_same_sum<T>(dynamic: DynamicInclusiveRange<T>, expected: RangeInclusive<T>)
+where
+    T: Integer + Sum + Into<i128> + TryFrom<i128>,
+    DynamicInclusiveRange<T>: Iterator<Item = T>,
+    RangeInclusive<T>: Iterator<Item = T> + Clone,
+{
+    let mut partial = 0i128;
+    for value in expected.clone() {
+        partial += value.into();
+        if T::try_from(partial).is_err() {
+            return;
+        }
+    }
+    assert_eq!(dynamic.sum::<T>(), expected.sum::<T>());
+}
+
+#[test]
+fn sum_u8_pairs() {
+    for from in u8::MIN..=u8::MAX {
+        for to in u8::MIN..=u8::MAX {
+            assert_same_sum(DynamicInclusiveRange::new(from, to), from..=to);
+        }
+    }
+}
+
+#[test]
+fn sum_i8_pairs() {
+    for from in i8::MIN..=i8::MAX {
+        for to in i8::MIN..=i8::MAX {
+            assert_same_sum(DynamicInclusiveRange::new(from, to), from..=to);
+        }
+    }
+}
+
+#[test]
+fn sum_max_edges() {
+    // Only the ranges of at most one value at the top of the type can be summed without
+    // overflowing it.
+    for &(from, to) in &[
+        (u64::MAX, u64::MAX),
+        (u64::MAX - 1, u64::MAX - 1),
+        (u64::MAX, u64::MAX - 1),
+    ] {
+        assert_same_sum(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+    for &(from, to) in &[
+        (i64::MAX, i64::MAX),
+        (i64::MAX - 1, i64::MAX - 1),
+        (i64::MAX, i64::MAX - 1),
+    ] {
+        assert_same_sum(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+    let range = DynamicInclusiveRange::from_bounds((Bound::Excluded(u64::MAX), Bound::Unbounded));
+    assert_eq!(range.sum::<u64>(), 0);
+}
+
+/// The `u8` values the bounds are built from: the edges of the type and a few values around them.
+const EDGES: [u8; 7] = [0, 1, 2, 127, 253, 254, 255];
+
+/// Every bound built from the [`EDGES`].
+fn edge_bounds() -> Vec<Bound<u8>> {
+    let mut bounds = vec![Bound::Unbounded];
+    for &value in &EDGES {
+        bounds.push(Bound::Included(value));
+        bounds.push(Bound::Excluded(value));
+    }
+    bounds
+}
+
+/// Checks that `dynamic` yields exactly the values of `u8` that `range` contains, in order.
+fn assert_contains(dynamic: DynamicInclusiveRange<u8>, range: &impl RangeBounds<u8>) {
+    let expected: Vec<u8> = (u8::MIN..=u8::MAX)
+        .filter(|value| range.contains(value))
+        .collect();
+    assert_eq!(dynamic.len(), expected.len());
+    assert_eq!(dynamic.clone().rev().count(), expected.len());
+    assert_eq!(dynamic.collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn from_bounds_u8() {
+    for &start in &edge_bounds() {
+        for &end in &edge_bounds() {
+            let range = (start, end);
+            assert_contains(DynamicInclusiveRange::from_bounds(range), &range);
+        }
+    }
+    // The start right past the largest value and the end right before the smallest one.
+    let range = (Bound::Excluded(u8::MAX), Bound::Unbounded);
+    assert_contains(DynamicInclusiveRange::from_bounds(range), &range);
+    let range = (Bound::Unbounded, Bound::Excluded(u8::MIN));
+    assert_contains(DynamicInclusiveRange::from_bounds(range), &range);
+    assert_contains(DynamicInclusiveRange::from_bounds(..), &(..));
+    assert_contains(DynamicInclusiveRange::from_bounds(3..3), &(3..3));
+    assert_contains(
+        DynamicInclusiveRange::from_bounds(..=u8::MAX),
+        &(..=u8::MAX),
+    );
+}
+
+#[test]
+fn from_inclusive_u8() {
+    for &from in &EDGES {
+        for &to in &EDGES {
+            let range = from..=to;
+            assert_contains(range.clone().into(), &range);
+
+            // Partially consumed and exhausted ranges only yield what is left of them.
+            let mut range = from..=to;
+            while range.next().is_some() {
+                assert_contains(range.clone().into(), &range);
+            }
+            assert_contains(range.clone().into(), &range);
+            assert_eq!(DynamicInclusiveRange::from(range).next(), None);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn same_as_inclusive_u64(from in near_max(), to in near_max()) {
+        assert_same_ends(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+
+    #[test]
+    fn same_as_inclusive_short_u64(from in near_max(), len in 0..=2 * PREFIX as u64) {
+        let to = from.saturating_add(len);
+        assert_same(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+
+    #[test]
+    fn same_as_inclusive_i64(from in any::<i64>(), to in any::<i64>()) {
+        assert_same_ends(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+
+    #[test]
+    fn same_as_inclusive_u128(from in any::<u128>(), to in any::<u128>()) {
+        assert_same_ends(DynamicInclusiveRange::new(from, to), from..=to);
+    }
+}
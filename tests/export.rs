@@ -0,0 +1,42 @@
+use range_perf::harness::export::{self, Export};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// An empty directory of its own for the test `name`.
+fn empty_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("range-perf-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn export(started: u64) -> Export {
+    Export {
+        started,
+        environment: None,
+        results: Vec::new(),
+        samples: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn runs_started_in_the_same_second_are_kept() {
+    let dir = empty_dir("same-second");
+    let names: Vec<String> = (0..3)
+        .map(|_| {
+            let path = export::write(&export(1_000), &dir).unwrap();
+            assert!(path.with_extension("csv").is_file());
+            path.file_name().unwrap().to_string_lossy().into_owned()
+        })
+        .collect();
+    assert_eq!(names, ["1000.json", "1000-1.json", "1000-2.json"]);
+    assert_eq!(export::latest(&dir).unwrap(), Some(dir.join("1000-2.json")));
+
+    export::write(&export(999), &dir).unwrap();
+    assert_eq!(export::latest(&dir).unwrap(), Some(dir.join("1000-2.json")));
+    export::write(&export(1_001), &dir).unwrap();
+    let latest = export::latest(&dir).unwrap().unwrap();
+    assert_eq!(latest, dir.join("1001.json"));
+    assert_eq!(export::load(&latest).unwrap().started, 1_001);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,157 @@
+use range_perf::harness::scenario::{self, Problem, Scenario};
+use range_perf::harness::selection::Bound;
+use range_perf::harness::workload::Iteration;
+use std::path::Path;
+
+/// Parses `text` and returns the messages of the problems found in it.
+fn problems(text: &str) -> Vec<String> {
+    match scenario::parse(text) {
+        Ok(scenarios) => panic!("{:?} is accepted", scenarios),
+        Err(problems) => problems
+            .into_iter()
+            .map(|problem| problem.message)
+            .collect(),
+    }
+}
+
+#[test]
+fn example_is_valid() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios/example.toml");
+    let scenarios = scenario::load(&path).unwrap();
+    assert!(!scenarios.is_empty());
+}
+
+#[test]
+fn parses_every_key() {
+    let scenarios = scenario::parse(
+        r#"
+        [[scenario]]
+        type = "i16"
+        low = -1000
+        high = "max"
+
+        [[scenario]]
+        name = "top"
+        type = "u64"
+        low = "max-999"
+        high = ["max-1", "18446744073709551615"]
+        strategies = ["inclusive", "dynamic"]
+        workload = "rev"
+        iteration = "while-let"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        scenarios,
+        vec![
+            Scenario {
+                name: "scenario 1".into(),
+                integer: "i16".into(),
+                low: Bound::Value("-1000".into()),
+                highs: vec![Bound::Max(0)],
+                strategies: vec![],
+                workload: "sum".into(),
+                iteration: Iteration::Fold,
+            },
+            Scenario {
+                name: "top".into(),
+                integer: "u64".into(),
+                low: Bound::Max(999),
+                highs: vec![Bound::Max(1), Bound::Value("18446744073709551615".into())],
+                strategies: vec!["inclusive".into(), "dynamic".into()],
+                workload: "rev".into(),
+                iteration: Iteration::WhileLet,
+            },
+        ]
+    );
+    assert_eq!(
+        scenarios[1].bounds::<u64>(),
+        Ok((u64::MAX - 999, vec![u64::MAX - 1, u64::MAX]))
+    );
+}
+
+#[test]
+fn rejects_bounds_out_of_the_type() {
+    assert_eq!(
+        problems("[[scenario]]\ntype = \"u8\"\nlow = 256\nhigh = \"max\"\n"),
+        ["low 256 is out of the range of u8"]
+    );
+    assert_eq!(
+        problems("[[scenario]]\ntype = \"i8\"\nlow = \"min\"\nhigh = \"max-256\"\n"),
+        ["high max-256 is out of the range of i8"]
+    );
+    assert_eq!(
+        problems("[[scenario]]\ntype = \"u32\"\nlow = \"max\"\nhigh = \"max-1\"\n"),
+        ["low max is above high max-1"]
+    );
+}
+
+#[test]
+fn rejects_impossible_combinations() {
+    let found = problems(
+        r#"
+        [[scenario]]
+        type = "u128"
+        low = "min"
+        high = "max"
+        strategies = ["wide", "dynamic-stepped"]
+        workload = "index"
+        iteration = "for"
+        "#,
+    );
+    assert_eq!(found.len(), 4, "{:?}", found);
+    assert!(found[0].contains("only runs over u64"));
+    assert!(found[1].contains("can't be iterated with for"));
+    assert!(found[2].contains("\"wide\" is not available for u128"));
+    assert!(found[3].contains("\"dynamic-stepped\" is not available for u128 with the index"));
+
+    // The stepped strategies step through the ranges with every iteration style.
+    let scenarios = scenario::parse(
+        r#"
+        [[scenario]]
+        type = "i8"
+        low = "min"
+        high = "max"
+        strategies = ["dynamic-stepped", "dynamic"]
+        workload = "step 3"
+        iteration = "while-let"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(scenarios[0].iteration, Iteration::WhileLet);
+
+    let found = problems("[[scenario]]\ntype = \"u8\"\nlow = 0\nhigh = 1\nworkload = \"step 0\"\n");
+    assert_eq!(found, ["invalid step in \"step 0\""]);
+    let found = problems("[[scenario]]\ntype = \"u9\"\nlow = 0\nhigh = 1\n");
+    assert!(found[0].starts_with("unknown type \"u9\""));
+}
+
+#[test]
+fn reports_the_lines() {
+    let text = "[[scenario]]\ntype = \"u8\"\nlow = 0\nhigh = 1\n\n[[scenario]]\ntype = \"u8\"\n\
+                low = 0\nhigh = 1\ncolour = \"red\"\n\n[[scenario]]\ntype = \"u8\"\nlow = 0\n";
+    assert_eq!(
+        scenario::parse(text),
+        Err(vec![
+            Problem {
+                line: 10,
+                message: "unknown key \"colour\"".into()
+            },
+            Problem {
+                line: 12,
+                message: "high is missing".into()
+            },
+        ])
+    );
+    assert_eq!(scenario::parse("[[scenario]\n").unwrap_err()[0].line, 1);
+    assert_eq!(problems(""), ["there are no [[scenario]] tables"]);
+}
+
+#[test]
+fn rejects_duplicate_names() {
+    let found = problems(
+        "[[scenario]]\nname = \"a\"\ntype = \"u8\"\nlow = 0\nhigh = 1\n\n\
+         [[scenario]]\nname = \"a\"\ntype = \"u16\"\nlow = 0\nhigh = 1\n",
+    );
+    assert_eq!(found, ["there is another scenario named \"a\""]);
+}
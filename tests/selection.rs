@@ -0,0 +1,185 @@
+use range_perf::harness::selection::{Baseline, Bound, Mode, Options, Selection};
+use std::time::Duration;
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn bounds_parse_and_print() {
+    for (text, bound) in [
+        ("min", Bound::Min(0)),
+        ("min+3", Bound::Min(3)),
+        ("max", Bound::Max(0)),
+        ("max-1", Bound::Max(1)),
+    ] {
+        assert_eq!(text.parse::<Bound>(), Ok(bound.clone()));
+        assert_eq!(bound.to_string(), text);
+    }
+    assert_eq!("-5".parse::<Bound>(), Ok(Bound::Value("-5".into())));
+    for invalid in &["", "max-", "max+1", "min-1", "maximum", "1.5"] {
+        assert!(invalid.parse::<Bound>().is_err(), "{:?} is parsed", invalid);
+    }
+}
+
+#[test]
+fn bounds_resolve_per_type() {
+    assert_eq!(Bound::Max(0).resolve::<u8>(), Ok(255));
+    assert_eq!(Bound::Max(1).resolve::<i8>(), Ok(126));
+    assert_eq!(Bound::Min(0).resolve::<i16>(), Ok(i16::MIN));
+    assert_eq!(Bound::Min(5).resolve::<i8>(), Ok(-123));
+    assert_eq!(Bound::Max(255).resolve::<u8>(), Ok(0));
+    assert_eq!(Bound::Max(0).resolve::<u128>(), Ok(u128::MAX));
+    assert_eq!(Bound::Value("-5".into()).resolve::<i64>(), Ok(-5));
+    assert!(Bound::Max(256).resolve::<u8>().is_err());
+    assert!(Bound::Value("256".into()).resolve::<u8>().is_err());
+    assert!(Bound::Value("-1".into()).resolve::<u32>().is_err());
+}
+
+#[test]
+fn selection_defaults_to_everything() {
+    let selection = Selection::default();
+    assert!(selection.strategy("wide"));
+    assert!(selection.workload("step 3"));
+    assert!(selection.integer::<u8>(true));
+    assert!(!selection.integer::<i8>(false));
+    assert_eq!(selection.bounds(0u8, vec![254, 255]), vec![254, 255]);
+    assert!(selection.up(7u64));
+}
+
+#[test]
+fn selection_filters() {
+    let selection = Selection {
+        strategies: vec!["dynamic".into()],
+        integers: vec!["i8".into()],
+        bounds: vec![Bound::Max(0), Bound::Value("200".into())],
+        workloads: vec!["step".into()],
+    };
+    assert!(selection.strategy("dynamic"));
+    assert!(!selection.strategy("dynamic-stepped"));
+    assert!(selection.workload("step 64"));
+    assert!(!selection.workload("sum"));
+    assert!(selection.integer::<i8>(false));
+    assert!(!selection.integer::<u8>(true));
+    // 200 doesn't fit into i8.
+    assert_eq!(selection.bounds(i8::MIN, vec![]), vec![127]);
+    assert_eq!(selection.bounds(0u8, vec![]), vec![255, 200]);
+    // The bounds below the start of the range are skipped.
+    assert_eq!(selection.bounds(201u8, vec![]), vec![255]);
+    assert!(selection.up(u64::MAX));
+    assert!(!selection.up(u64::MAX - 1));
+}
+
+#[test]
+fn options_parse() {
+    let options = parse(&[
+        "--bench",
+        "--strategy",
+        "dynamic,inclusive",
+        "--type",
+        "u8",
+        "--type",
+        "i64",
+        "--bound",
+        "max,max-1",
+        "--workload",
+        "sum",
+        "--sample-size",
+        "20",
+        "--measurement-time",
+        "0.5",
+        "--list",
+        "--scenario",
+        "scenarios/example.toml",
+        "ranges",
+    ])
+    .unwrap();
+    assert_eq!(
+        options,
+        Options {
+            selection: Selection {
+                strategies: vec!["dynamic".into(), "inclusive".into()],
+                integers: vec!["u8".into(), "i64".into()],
+                bounds: vec![Bound::Max(0), Bound::Max(1)],
+                workloads: vec!["sum".into()],
+            },
+            filter: Some("ranges".into()),
+            sample_size: Some(20),
+            measurement_time: Some(Duration::from_millis(500)),
+            baseline: None,
+            noplot: false,
+            mode: Mode::List,
+            scenarios: vec!["scenarios/example.toml".into()],
+        }
+    );
+    assert_eq!(parse(&[]), Ok(Options::default()));
+}
+
+#[test]
+fn options_parse_the_criterion_flags() {
+    let options = parse(&["--save-baseline", "main", "--noplot", "--bench"]).unwrap();
+    assert_eq!(options.baseline, Some(Baseline::Save("main".into())));
+    assert!(options.noplot);
+    let options = parse(&["-b", "main", "ranges"]).unwrap();
+    assert_eq!(options.baseline, Some(Baseline::Compare("main".into())));
+    assert_eq!(options.filter, Some("ranges".into()));
+    assert!(!options.noplot);
+    assert_eq!(parse(&["-n"]).map(|options| options.noplot), Ok(true));
+}
+
+#[test]
+fn options_parse_the_modes() {
+    let mode = |args: &[&str]| parse(args).map(|options| options.mode);
+    assert_eq!(mode(&[]), Ok(Mode::Bench));
+    assert_eq!(mode(&["--codegen"]), Ok(Mode::Codegen));
+    assert_eq!(mode(&["--summary"]), Ok(Mode::Summary(None)));
+    assert_eq!(
+        mode(&["--pairwise", "run.json"]),
+        Ok(Mode::Pairwise(Some("run.json".into())))
+    );
+    assert_eq!(
+        mode(&["--compare", "base.json", "--noplot"]),
+        Ok(Mode::Compare {
+            baseline: "base.json".into(),
+            current: None,
+        })
+    );
+    assert_eq!(
+        mode(&["--compare", "base.json", "run.json"]),
+        Ok(Mode::Compare {
+            baseline: "base.json".into(),
+            current: Some("run.json".into()),
+        })
+    );
+
+    // The modes that run the benchmarks go along with the selection, wherever they are.
+    let options = parse(&["--type", "u8", "--sanity", "ranges"]).unwrap();
+    assert_eq!(options.mode, Mode::Sanity);
+    assert_eq!(options.selection.integers, ["u8"]);
+    assert_eq!(options.filter, Some("ranges".into()));
+}
+
+#[test]
+fn options_reject_invalid_arguments() {
+    for args in &[
+        &["--strategy", "fast"][..],
+        &["--type", "u256"],
+        &["--workload", "sum,product"],
+        &["--bound", "max+1"],
+        &["--sample-size", "1"],
+        &["--measurement-time", "0"],
+        &["--measurement-time", "soon"],
+        &["--strategy"],
+        &["--scenario"],
+        &["--verbose"],
+        &["--save-baseline"],
+        &["--save-baseline", "main", "--baseline", "main"],
+        &["--compare"],
+        &["--compare", "--summary"],
+        &["--list", "--sanity"],
+        &["--summary", "run.json", "--codegen"],
+        &["ranges", "collect"],
+    ] {
+        assert!(parse(args).is_err(), "{:?} is accepted", args);
+    }
+}
@@ -0,0 +1,82 @@
+use range_perf::harness::stats::{self, Comparison, Effect};
+use std::f64::consts::PI;
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() < tolerance,
+        "{} is not {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn student_t_closed_forms() {
+    for &t in &[0., 0.1, 0.5, 1., 2., 5., 20., 100.] {
+        // The Cauchy distribution.
+        assert_close(
+            stats::student_t_two_sided(t, 1.),
+            1. - 2. / PI * f64::atan(t),
+            1e-10,
+        );
+        assert_close(
+            stats::student_t_two_sided(t, 2.),
+            1. - t / (t * t + 2.).sqrt(),
+            1e-10,
+        );
+    }
+}
+
+#[test]
+fn student_t_critical_values() {
+    // The two-sided 5% critical values from the tables.
+    assert_close(stats::student_t_two_sided(12.706, 1.), 0.05, 1e-4);
+    assert_close(stats::student_t_two_sided(2.228, 10.), 0.05, 1e-4);
+    assert_close(stats::student_t_two_sided(2.042, 30.), 0.05, 1e-4);
+    assert_close(stats::student_t_two_sided(1.960, 1e6), 0.05, 1e-4);
+    assert_close(stats::student_t_two_sided(-2.228, 10.), 0.05, 1e-4);
+}
+
+#[test]
+fn welch_by_hand() {
+    let a = [1., 2., 3., 4., 5.];
+    let b = [2., 4., 6., 8., 10.];
+    let welch = stats::welch(&a, &b);
+    // The means are 3 and 6, the variances are 2.5 and 10.
+    assert_close(welch.t, -3. / 2.5f64.sqrt(), 1e-12);
+    assert_close(welch.df, 6.25 / (0.25 / 4. + 4. / 4.), 1e-12);
+    assert!(welch.p > 0.05 && welch.p < 0.2, "{:?}", welch);
+    assert_close(stats::cohens_d(&a, &b), -3. / 6.25f64.sqrt(), 1e-12);
+}
+
+#[test]
+fn constant_samples() {
+    let same = stats::welch(&[1., 1., 1.], &[1., 1.]);
+    assert_eq!(same.p, 1.);
+    let different = Comparison::new(&[2., 2., 2.], &[1., 1.]);
+    assert_eq!(different.welch.p, 0.);
+    assert_eq!(different.ratio, 2.);
+    assert!(different.significant());
+    assert_eq!(different.effect(), Effect::Large);
+}
+
+#[test]
+fn noise_is_not_significant() {
+    // The same values shuffled differ by nothing.
+    let a: Vec<f64> = (0..100).map(|i| 100. + f64::from(i % 7)).collect();
+    let mut b = a.clone();
+    b.reverse();
+    let comparison = Comparison::new(&a, &b);
+    assert!(!comparison.significant());
+    assert_eq!(comparison.effect(), Effect::Negligible);
+
+    // A shift of a tenth of the spread over a hundred samples is not detectable either.
+    let shifted: Vec<f64> = a.iter().map(|x| x + 0.2).collect();
+    assert!(!Comparison::new(&shifted, &a).significant());
+
+    // But a shift of the whole spread is.
+    let shifted: Vec<f64> = a.iter().map(|x| x + 2.).collect();
+    let comparison = Comparison::new(&shifted, &a);
+    assert!(comparison.significant());
+    assert_eq!(comparison.effect(), Effect::Large);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{assert_same, assert_same_start, near_max, PREFIX};
+use proptest::prelude::*;
+use range_perf::DynamicSteppedRange;
+
+/// The steps the ranges of the small types are checked with, from the smallest one to ones larger
+/// than any span of the type.
+const STEPS: [usize; 8] = [1, 2, 3, 7, 64, 255, 256, 1000];
+
+#[test]
+fn all_u8_pairs() {
+    for &step in &STEPS {
+        for from in u8::MIN..=u8::MAX {
+            for to in u8::MIN..=u8::MAX {
+                assert_same(
+                    DynamicSteppedRange::new(from, to, step),
+                    (from..=to).step_by(step),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn all_i8_pairs() {
+    for &step in &STEPS {
+        for from in i8::MIN..=i8::MAX {
+            for to in i8::MIN..=i8::MAX {
+                assert_same(
+                    DynamicSteppedRange::new(from, to, step),
+                    (from..=to).step_by(step),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn max_edges() {
+    // The last value is the largest one of the type only when the step divides the span.
+    for &step in &[1, 2, 3, 5] {
+        let from = u64::MAX - 30;
+        assert_same(
+            DynamicSteppedRange::new(from, u64::MAX, step),
+            (from..=u64::MAX).step_by(step),
+        );
+    }
+    assert_same(
+        DynamicSteppedRange::new(u64::MAX, u64::MAX, 1),
+        (u64::MAX..=u64::MAX).step_by(1),
+    );
+    let (from, to) = (u64::MAX, u64::MAX - 1);
+    assert_same(
+        DynamicSteppedRange::new(from, to, 1),
+        (from..=to).step_by(1),
+    );
+    assert_same(
+        DynamicSteppedRange::new(0, u64::MAX, usize::MAX),
+        (0..=u64::MAX).step_by(usize::MAX),
+    );
+    assert_same_start(
+        DynamicSteppedRange::new(0, u64::MAX, 1),
+        (0..=u64::MAX).step_by(1),
+    );
+    assert_same_start(
+        DynamicSteppedRange::new(1, u64::MAX, 2),
+        (1..=u64::MAX).step_by(2),
+    );
+    assert_same_start(
+        DynamicSteppedRange::new(i128::MIN, i128::MAX, 3),
+        (i128::MIN..=i128::MAX).step_by(3),
+    );
+}
+
+#[test]
+#[should_panic(expected = "step must be non-zero")]
+fn zero_step() {
+    DynamicSteppedRange::new(0u8, 10, 0);
+}
+
+proptest! {
+    #[test]
+    fn same_as_step_by_u64(from in near_max(), to in near_max(), step in 1..=PREFIX) {
+        assert_same_start(DynamicSteppedRange::new(from, to, step), (from..=to).step_by(step));
+    }
+
+    #[test]
+    fn same_as_step_by_short_u64(
+        from in near_max(),
+        len in 0..=2 * PREFIX as u64,
+        step in 1..=2 * PREFIX,
+    ) {
+        let to = from.saturating_add(len);
+        assert_same(DynamicSteppedRange::new(from, to, step), (from..=to).step_by(step));
+    }
+
+    #[test]
+    fn same_as_step_by_i64(from in any::<i64>(), to in any::<i64>(), step in 1..=usize::MAX) {
+        assert_same_start(DynamicSteppedRange::new(from, to, step), (from..=to).step_by(step));
+    }
+}